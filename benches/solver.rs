@@ -0,0 +1,131 @@
+// Solver benchmarks: the reference puzzles, generated boards of growing size and puzzles that
+// need guessing.
+//
+//   cargo bench --bench solver -- --save-baseline before   # on the old commit
+//   cargo bench --bench solver -- --baseline before        # on the new one, prints the change
+//
+// Before timing, every puzzle prints one line with the solver stats and the peak heap usage
+// of a solve. Boards are generated from a fixed seed, so these lines can be diffed directly.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+
+use nonogram_solver::analysis::{self, Uniqueness};
+use nonogram_solver::board::{Board, Cell};
+use nonogram_solver::puzzles;
+use nonogram_solver::solver::{self, SolveOutcome, SolveStatus};
+
+struct CountingAlloc;
+
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let allocated = ALLOCATED.fetch_add(layout.size(), Ordering::Relaxed) + layout.size();
+        PEAK.fetch_max(allocated, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        ALLOCATED.fetch_sub(layout.size(), Ordering::Relaxed);
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAlloc = CountingAlloc;
+
+// bytes allocated on top of what was live before `f` ran, at the worst moment
+fn peak_memory<T>(f: impl FnOnce() -> T) -> (T, usize) {
+    let before = ALLOCATED.load(Ordering::Relaxed);
+    PEAK.store(before, Ordering::Relaxed);
+    let result = f();
+    (result, PEAK.load(Ordering::Relaxed) - before)
+}
+
+fn report(name: &str, board: &Board, solve: impl Fn(&mut Board) -> SolveOutcome) {
+    let mut board = board.clone();
+    let (outcome, memory) = peak_memory(|| solve(&mut board));
+    println!(
+        "{:<16} {:?}: iterations {}, lines processed {}, cells solved {}, peak memory {} B",
+        name,
+        outcome.status,
+        outcome.stats.iterations,
+        outcome.stats.lines_processed,
+        outcome.stats.cells_solved,
+        memory
+    );
+}
+
+// a blank board with the clues of a random grid, about 60% of the cells painted
+fn generated_board(size: usize, seed: u64) -> Board {
+    let mut state = seed;
+    let mut board = Board::new(size, size);
+    for cell in board.data.iter_mut().flatten() {
+        state = state
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        *cell = if (state >> 33) % 100 < 60 {
+            Cell::On
+        } else {
+            Cell::Off
+        };
+    }
+    board.generate_clues_from_board();
+    board.clear_board();
+    board
+}
+
+fn line_rules(board: &mut Board) -> SolveOutcome {
+    solver::solve(board, true)
+}
+
+// line rules first, guessing for whatever they leave
+fn line_rules_and_search(board: &mut Board) -> SolveOutcome {
+    let mut outcome = solver::solve(board, true);
+    if !outcome.is_solved() && analysis::complete_by_search(board) == Uniqueness::Unique {
+        outcome.status = SolveStatus::Solved;
+    }
+    outcome
+}
+
+fn reference_puzzles(c: &mut Criterion) {
+    let mut group = c.benchmark_group("reference");
+    for (name, board) in puzzles::reference_puzzles() {
+        report(name, &board, line_rules);
+        group.bench_with_input(BenchmarkId::from_parameter(name), &board, |b, board| {
+            b.iter(|| line_rules(&mut board.clone()))
+        });
+    }
+    group.finish();
+}
+
+fn generated_boards(c: &mut Criterion) {
+    let mut group = c.benchmark_group("generated");
+    for &size in &[10, 20, 30, 40, 50] {
+        let board = generated_board(size, size as u64);
+        report(&format!("{}x{}", size, size), &board, line_rules);
+        group.bench_with_input(BenchmarkId::from_parameter(size), &board, |b, board| {
+            b.iter(|| line_rules(&mut board.clone()))
+        });
+    }
+    group.finish();
+}
+
+fn hard_puzzles(c: &mut Criterion) {
+    let mut group = c.benchmark_group("hard");
+    group.sample_size(20);
+    for (name, board) in puzzles::hard_puzzles() {
+        report(name, &board, line_rules_and_search);
+        group.bench_with_input(BenchmarkId::from_parameter(name), &board, |b, board| {
+            b.iter(|| line_rules_and_search(&mut board.clone()))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, reference_puzzles, generated_boards, hard_puzzles);
+criterion_main!(benches);
@@ -0,0 +1,14 @@
+// Arbitrary bytes for the board loader; whatever loads is solved as well.
+// Run with `cargo fuzz run board_json`.
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+use nonogram_solver::board::Board;
+use nonogram_solver::solver;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(mut board) = serde_json::from_slice::<Board>(data) {
+        solver::solve(&mut board, false);
+    }
+});
@@ -0,0 +1,51 @@
+// Arbitrary hints and cells for every line rule. Run with `cargo fuzz run line_rules`.
+#![no_main]
+
+use libfuzzer_sys::arbitrary::{self, Arbitrary};
+use libfuzzer_sys::fuzz_target;
+
+use nonogram_solver::board::{Board, Cell, Clue};
+use nonogram_solver::solver;
+
+#[derive(Arbitrary, Debug)]
+struct Input {
+    hints: Vec<u8>,
+    cells: Vec<u8>,
+    pos: u8,
+}
+
+fuzz_target!(|input: Input| {
+    let hints = input.hints.iter().map(|&h| h as usize).collect::<Vec<_>>();
+    let clue = Clue::new(hints.clone());
+    let line = input
+        .cells
+        .iter()
+        .map(|b| match b % 3 {
+            0 => Cell::None,
+            1 => Cell::On,
+            _ => Cell::Off,
+        })
+        .collect::<Vec<_>>();
+
+    let rules: [&dyn Fn(&mut [Cell]); 8] = [
+        &|line| solver::simple_boxes(&hints, line),
+        &|line| solver::mark_complete_row(&clue, line),
+        &|line| solver::fill_first_and_last_block_if_possible(&hints, line),
+        &|line| solver::cross_impossible_region(&hints, line),
+        &|line| solver::enclose_completed_region(&clue, line),
+        &|line| solver::fill_only_one_possible_hint_region(&clue, line),
+        &|line| solver::block_intersection(&clue, line),
+        &|line| {
+            solver::trim_finished_hints(&hints, line);
+        },
+    ];
+    for rule in rules.iter() {
+        rule(&mut line.clone());
+    }
+
+    let pos = input.pos as usize;
+    Board::count_block_length(&line[..], pos, false);
+    Board::count_block_length(&line[..], pos, true);
+    Board::get_consecutive_regions(&line[..], true, None);
+    Board::find_first_cell_such_that(&line[..], |cell| *cell == Cell::On, true);
+});
@@ -0,0 +1,136 @@
+#ifndef NONOGRAM_SOLVER_H
+#define NONOGRAM_SOLVER_H
+
+/* Generated by cbindgen from src/ffi.rs, do not edit by hand. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+typedef enum NonogramCell {
+  NONOGRAM_CELL_UNKNOWN = 0,
+  NONOGRAM_CELL_FILLED = 1,
+  NONOGRAM_CELL_EMPTY = 2,
+} NonogramCell;
+
+typedef enum NonogramStatus {
+  /**
+   * Every cell is known.
+   */
+  NONOGRAM_STATUS_SOLVED = 0,
+  /**
+   * Not solved yet, or the line rules got stuck and guessing wasn't allowed.
+   */
+  NONOGRAM_STATUS_UNSOLVED = 1,
+  /**
+   * Guessing found that the clues (and the known cells) have no solution.
+   */
+  NONOGRAM_STATUS_NO_SOLUTION = 2,
+  /**
+   * Guessing found more than one solution, the board is left as the line rules solved it.
+   */
+  NONOGRAM_STATUS_MULTIPLE_SOLUTIONS = 3,
+  /**
+   * A null pointer or out of range argument was passed.
+   */
+  NONOGRAM_STATUS_INVALID_ARGUMENT = 4,
+  /**
+   * The solver failed unexpectedly, the puzzle is left unchanged.
+   */
+  NONOGRAM_STATUS_INTERNAL_ERROR = 5,
+  /**
+   * The clues are inconsistent (e.g. hints longer than the line), the puzzle is left unchanged.
+   */
+  NONOGRAM_STATUS_INVALID_CLUES = 6,
+} NonogramStatus;
+
+/**
+ * Puzzle handle, created by `nonogram_puzzle_new` and released by `nonogram_puzzle_free`.
+ */
+typedef struct NonogramPuzzle NonogramPuzzle;
+
+typedef struct NonogramSolveOptions {
+  /**
+   * Forget the known cells before solving.
+   */
+  bool clear_board;
+  /**
+   * Finish boards the line rules can't solve by guessing.
+   */
+  bool allow_guessing;
+} NonogramSolveOptions;
+
+/**
+ * Creates a blank puzzle. The hints of all rows are passed one after another in `row_hints`,
+ * `row_hint_counts[i]` is the number of hints of row `i`, and the same for columns.
+ * Returns null if a required pointer is null or the puzzle is too big.
+ *
+ * # Safety
+ * `row_hint_counts` must point to `rows` values and `row_hints` to as many values as their sum
+ * (likewise for the column arguments).
+ */
+struct NonogramPuzzle *nonogram_puzzle_new(size_t rows,
+                                           size_t cols,
+                                           const size_t *row_hints,
+                                           const size_t *row_hint_counts,
+                                           const size_t *col_hints,
+                                           const size_t *col_hint_counts);
+
+/**
+ * # Safety
+ * `puzzle` must come from `nonogram_puzzle_new` and must not be used afterwards.
+ */
+void nonogram_puzzle_free(struct NonogramPuzzle *puzzle);
+
+/**
+ * # Safety
+ * `puzzle` must be null or come from `nonogram_puzzle_new`.
+ */
+size_t nonogram_puzzle_rows(const struct NonogramPuzzle *puzzle);
+
+/**
+ * # Safety
+ * `puzzle` must be null or come from `nonogram_puzzle_new`.
+ */
+size_t nonogram_puzzle_cols(const struct NonogramPuzzle *puzzle);
+
+/**
+ * Marks a cell as known before solving. `cell` is a `NonogramCell`. Returns false if the cell
+ * is out of range or `cell` isn't one of the `NonogramCell` values.
+ *
+ * # Safety
+ * `puzzle` must be null or come from `nonogram_puzzle_new`.
+ */
+bool nonogram_puzzle_set_cell(struct NonogramPuzzle *puzzle, size_t row, size_t col, uint32_t cell);
+
+/**
+ * Writes the cell to `cell`. Returns false if the cell is out of range.
+ *
+ * # Safety
+ * `puzzle` must be null or come from `nonogram_puzzle_new`, `cell` must be null or writable.
+ */
+bool nonogram_puzzle_get_cell(const struct NonogramPuzzle *puzzle,
+                              size_t row,
+                              size_t col,
+                              enum NonogramCell *cell);
+
+/**
+ * Solves the puzzle in place. `options` may be null to use the defaults (all false).
+ *
+ * # Safety
+ * `puzzle` must be null or come from `nonogram_puzzle_new`, `options` must be null or valid.
+ */
+enum NonogramStatus nonogram_puzzle_solve(struct NonogramPuzzle *puzzle,
+                                          const struct NonogramSolveOptions *options);
+
+/**
+ * Status of the last `nonogram_puzzle_solve` call, `Unsolved` before the first one.
+ *
+ * # Safety
+ * `puzzle` must be null or come from `nonogram_puzzle_new`.
+ */
+enum NonogramStatus nonogram_puzzle_status(const struct NonogramPuzzle *puzzle);
+
+#endif /* NONOGRAM_SOLVER_H */
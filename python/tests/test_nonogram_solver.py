@@ -0,0 +1,96 @@
+# Run from the repository root after building the module:
+#   cargo build --release --no-default-features --features python
+#   cp target/release/libnonogram_solver.so python/nonogram_solver.so
+#   PYTHONPATH=python python3 -m unittest discover -s python/tests
+
+import json
+import unittest
+
+import nonogram_solver as ns
+
+
+class BoardTest(unittest.TestCase):
+    def test_blank_board(self):
+        board = ns.Board([[2], [1]], [[2], [1]])
+
+        self.assertEqual((board.rows, board.cols), (2, 2))
+        self.assertEqual(board.grid, ["  ", "  "])
+        self.assertEqual(board.row_clues, [[2], [1]])
+        self.assertEqual(board.col_clues, [[2], [1]])
+
+    def test_grid_round_trip(self):
+        board = ns.Board([[1], [1]], [[1], [1]], grid=["X.", "  "])
+        self.assertEqual(board.grid, ["X.", "  "])
+
+        board.grid = [".X", "X."]
+        self.assertEqual(board.grid, [".X", "X."])
+
+        board.clear()
+        self.assertEqual(board.grid, ["  ", "  "])
+
+    def test_invalid_grid(self):
+        board = ns.Board([[1]], [[1]])
+
+        with self.assertRaises(ValueError):
+            board.grid = ["#"]
+        with self.assertRaises(ValueError):
+            board.grid = ["X", "X"]
+        with self.assertRaises(ValueError):
+            ns.Board([[1]], [[1]], grid=["XX"])
+
+    def test_rules(self):
+        board = ns.Board([[1]], [[1]])
+        self.assertEqual([(r.n, r.is_col, r.hints) for r in board.rules], [(0, False, [1]), (0, True, [1])])
+
+        board.rules = [ns.Rule(0, False, []), ns.Rule(0, True, [])]
+        self.assertEqual(board.row_clues, [[]])
+        self.assertEqual(repr(board.rules[1]), "Rule(0, True, [])")
+        with self.assertRaises(ValueError):
+            board.rules = [ns.Rule(0, False, [1])]
+
+    def test_json(self):
+        board = ns.Board.from_json('{"rows":1,"cols":2,"row_clues":[[2]],"col_clues":[[1],[1]]}')
+        self.assertEqual(json.loads(board.to_json())["grid"], ["  "])
+
+        with self.assertRaises(ValueError):
+            ns.Board.from_json("{}")
+
+
+class SolverTest(unittest.TestCase):
+    def test_solve(self):
+        board = ns.Board([[2], [1]], [[2], [1]])
+        result = ns.solve(board)
+
+        self.assertEqual(result["status"], "solved")
+        self.assertEqual(result["stats"]["cells_solved"], 4)
+        self.assertEqual(board.grid, ["XX", "X."])
+
+    def test_solve_keeps_known_cells(self):
+        board = ns.Board([[1], [1]], [[1], [1]], grid=["X ", "  "])
+
+        self.assertEqual(ns.solve(board, clear_board=False)["status"], "solved")
+        self.assertEqual(board.grid, ["X.", ".X"])
+        self.assertEqual(ns.solve(board)["status"], "unsolved")
+
+    def test_uniqueness_and_difficulty(self):
+        self.assertEqual(ns.check_uniqueness(ns.Board([[2], [1]], [[2], [1]])), "unique")
+        self.assertEqual(ns.check_uniqueness(ns.Board([[1], [1]], [[1], [1]])), "multiple")
+        self.assertEqual(ns.check_uniqueness(ns.Board([[2], []], [[], [2]])), "no_solution")
+
+        self.assertEqual(ns.grade_difficulty(ns.Board([[2], [1]], [[2], [1]])), "easy")
+        self.assertIsNone(ns.grade_difficulty(ns.Board([[1], [1]], [[1], [1]])))
+
+    def test_clue_generation(self):
+        board = ns.Board.from_grid(["X.X", "XXX"])
+
+        self.assertEqual(board.row_clues, [[1, 1], [3]])
+        self.assertEqual(board.col_clues, [[2], [1], [2]])
+        self.assertEqual(board.grid, ["X.X", "XXX"])
+
+        board.grid = ["...", ".X."]
+        board.generate_clues()
+        self.assertEqual(board.row_clues, [[], [1]])
+
+
+if __name__ == "__main__":
+    unittest.main()
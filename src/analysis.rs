@@ -0,0 +1,403 @@
+use serde::{Deserialize, Serialize};
+
+use crate::board::{Board, Cell};
+use crate::line::Line;
+use crate::solver;
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Uniqueness {
+    Unique,
+    Multiple,
+    NoSolution,
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Difficulty {
+    Easy,   // the line rules of `solver::solve` are enough
+    Medium, // needs complete line reasoning (every placement of the hints is considered)
+    Hard,   // needs guessing
+}
+
+// how far a line of a board being played is from its clue
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum LineStatus {
+    Satisfied,             // the painted blocks are exactly the hints
+    Contradiction,         // no placement of the hints agrees with the set cells
+    Unfinished(Vec<bool>), // for every hint, whether its block is painted and bounded
+}
+
+#[derive(Debug)]
+struct Contradiction;
+
+// the search visited more boards than it was allowed to
+#[derive(Debug, Eq, PartialEq)]
+pub struct OutOfBudget;
+
+// boards with invalid clues have no solution
+pub fn check_uniqueness(board: &Board) -> Uniqueness {
+    check_uniqueness_within(board, usize::MAX).unwrap()
+}
+
+// like `check_uniqueness`, giving up after guessing on `max_nodes` boards
+pub fn check_uniqueness_within(
+    board: &Board, max_nodes: usize,
+) -> Result<Uniqueness, OutOfBudget> {
+    if !board.validate().is_empty() {
+        return Ok(Uniqueness::NoSolution);
+    }
+
+    let mut board = board.clone();
+    board.clear_board();
+
+    let (mut solutions, mut budget) = (vec![], max_nodes);
+    search(board, 2, &mut solutions, &mut budget)?;
+    Ok(match solutions.len() {
+        0 => Uniqueness::NoSolution,
+        1 => Uniqueness::Unique,
+        _ => Uniqueness::Multiple,
+    })
+}
+
+// None if the puzzle doesn't have exactly one solution
+pub fn grade_difficulty(board: &Board) -> Option<Difficulty> {
+    grade_difficulty_within(board, usize::MAX).unwrap()
+}
+
+pub fn grade_difficulty_within(
+    board: &Board, max_nodes: usize,
+) -> Result<Option<Difficulty>, OutOfBudget> {
+    if check_uniqueness_within(board, max_nodes)? != Uniqueness::Unique {
+        return Ok(None);
+    }
+    Ok(Some(grade_unique(board)))
+}
+
+// the difficulty of a board already known to have exactly one solution, without searching
+pub fn grade_unique(board: &Board) -> Difficulty {
+    let mut board = board.clone();
+    if solver::solve(&mut board, true).is_solved() {
+        return Difficulty::Easy;
+    }
+
+    board.clear_board();
+    match propagate(&mut board) {
+        Ok(()) if solver::is_solved(&board) => Difficulty::Medium,
+        _ => Difficulty::Hard,
+    }
+}
+
+// the only solution of the board, if there is exactly one
+pub fn find_unique_solution(board: &Board) -> Option<Board> {
+    let mut board = board.clone();
+    board.clear_board();
+
+    match complete_by_search(&mut board) {
+        Uniqueness::Unique => Some(board),
+        _ => None,
+    }
+}
+
+// fills in the rest of the board by guessing, keeping the cells that are already set.
+// The board is only changed if the solution is unique.
+pub fn complete_by_search(board: &mut Board) -> Uniqueness {
+    if !board.validate().is_empty() {
+        return Uniqueness::NoSolution;
+    }
+
+    let (mut solutions, mut budget) = (vec![], usize::MAX);
+    search(board.clone(), 2, &mut solutions, &mut budget).unwrap();
+
+    match solutions.len() {
+        0 => Uniqueness::NoSolution,
+        1 => {
+            *board = solutions.remove(0);
+            Uniqueness::Unique
+        }
+        _ => Uniqueness::Multiple,
+    }
+}
+
+// whether some placement of the hints agrees with the set cells of the line
+pub fn line_fits<L: Line + ?Sized>(hints: &[usize], row: &L) -> bool {
+    settle_line(hints, &mut row.cells()[..]).is_ok()
+}
+
+pub fn line_status<L: Line + ?Sized>(hints: &[usize], row: &L) -> LineStatus {
+    if !line_fits(hints, row) {
+        return LineStatus::Contradiction;
+    }
+
+    let regions = Board::get_consecutive_regions(row, false, None);
+    if regions.iter().map(|r| r.1).eq(hints.iter().copied()) {
+        return LineStatus::Satisfied;
+    }
+
+    // a block is done if it is bounded by Off cells or the edges, and there are only set cells
+    // between it and the start of the line (it matches the first hints then) or the end
+    let len = row.len();
+    let is_set = |from: usize, to: usize| (from..to).all(|i| row[i] != Cell::None);
+    let mut done = vec![false; hints.len()];
+
+    let mut from = 0;
+    for (j, &(start, length)) in regions.iter().enumerate().take(hints.len()) {
+        let end = start + length;
+        let bounded = end == len || row[end] == Cell::Off;
+        if !bounded || !is_set(from, start) || hints[j] != length {
+            break;
+        }
+        done[j] = true;
+        from = end;
+    }
+
+    let mut to = len;
+    for (j, &(start, length)) in regions.iter().rev().enumerate().take(hints.len()) {
+        let j = hints.len() - 1 - j;
+        let bounded = start == 0 || row[start - 1] == Cell::Off;
+        if !bounded || !is_set(start + length, to) || hints[j] != length {
+            break;
+        }
+        done[j] = true;
+        to = start;
+    }
+
+    LineStatus::Unfinished(done)
+}
+
+fn search(
+    mut board: Board, limit: usize, solutions: &mut Vec<Board>, budget: &mut usize,
+) -> Result<(), OutOfBudget> {
+    if solutions.len() >= limit {
+        return Ok(());
+    }
+    if *budget == 0 {
+        return Err(OutOfBudget);
+    }
+    *budget -= 1;
+    if propagate(&mut board).is_err() {
+        return Ok(());
+    }
+
+    let unknown_cell = board.data.iter().enumerate().find_map(|(n_row, row)| {
+        row.iter()
+            .position(|c| *c == Cell::None)
+            .map(|n_col| (n_row, n_col))
+    });
+
+    match unknown_cell {
+        None => solutions.push(board),
+        Some((n_row, n_col)) => {
+            for &guess in &[Cell::On, Cell::Off] {
+                let mut guessed_board = board.clone();
+                guessed_board.data[n_row][n_col] = guess;
+                search(guessed_board, limit, solutions, budget)?;
+                if solutions.len() >= limit {
+                    return Ok(());
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+// applies complete line reasoning to every line until nothing changes
+fn propagate(board: &mut Board) -> Result<(), Contradiction> {
+    let lines = board
+        .lines()
+        .map(|(line, clue)| (line, clue.clone()))
+        .collect::<Vec<_>>();
+    loop {
+        let mut changed = false;
+        for (line, clue) in lines.iter() {
+            let mut col_or_row = board.get_line(*line);
+            changed |= settle_line(&clue.hints, &mut col_or_row)?;
+        }
+
+        if !changed {
+            return Ok(());
+        }
+    }
+}
+
+// the hint a run of On cells most likely belongs to: the one that covers it in the most
+// placements of the hints agreeing with the line. None if no placement does.
+pub fn likely_block<L: Line + ?Sized>(
+    hints: &[usize],
+    row: &L,
+    start: usize,
+    length: usize,
+) -> Option<usize> {
+    let placements = Placements::new(hints, row);
+    let counts = hints.iter().enumerate().map(|(j, &hint)| {
+        if hint < length {
+            return 0.0;
+        }
+        let first = (start + length).saturating_sub(hint);
+        (first..=start)
+            .map(|block_start| placements.with_block(hints, j, block_start))
+            .sum::<f64>()
+    });
+
+    let mut best = None;
+    for (j, count) in counts.enumerate() {
+        if count > best.map_or(0.0, |(_, best_count)| best_count) {
+            best = Some((j, count));
+        }
+    }
+    best.map(|(j, _)| j)
+}
+
+// Ways to place the hints on a line so that every On cell is covered and no block covers an Off
+// cell. prefix[j][i] - first j hints in 0..i, suffix[j][i] - hints from j in i..len
+struct Placements {
+    len: usize,
+    on: Vec<bool>,
+    off_before: Vec<usize>, // off_before[i] - number of Off cells in 0..i
+    prefix: Vec<Vec<f64>>,
+    suffix: Vec<Vec<f64>>,
+}
+
+impl Placements {
+    fn new<L: Line + ?Sized>(hints: &[usize], row: &L) -> Self {
+        let len = row.len();
+        let k = hints.len();
+        let on = (0..len).map(|i| row[i] == Cell::On).collect::<Vec<_>>();
+
+        let mut off_before = vec![0; len + 1];
+        for i in 0..len {
+            off_before[i + 1] = off_before[i] + (row[i] == Cell::Off) as usize;
+        }
+
+        let mut placements = Placements {
+            len,
+            on,
+            off_before,
+            prefix: vec![vec![0.0; len + 1]; k + 1],
+            suffix: vec![vec![0.0; len + 1]; k + 1],
+        };
+        let on = |i: usize| placements.on[i];
+
+        let mut prefix = vec![vec![0.0; len + 1]; k + 1];
+        prefix[0][0] = 1.0;
+        for i in 1..=len {
+            prefix[0][i] = if on(i - 1) { 0.0 } else { prefix[0][i - 1] };
+        }
+        for j in 1..=k {
+            let hint = hints[j - 1];
+            for i in 1..=len {
+                let ends_empty = if on(i - 1) { 0.0 } else { prefix[j][i - 1] };
+                let ends_with_block = if i < hint || !placements.block_fits(i - hint, hint) {
+                    0.0
+                } else if i == hint {
+                    (j == 1) as usize as f64
+                } else if on(i - hint - 1) {
+                    0.0
+                } else {
+                    prefix[j - 1][i - hint - 1]
+                };
+                prefix[j][i] = ends_empty + ends_with_block;
+            }
+        }
+
+        let mut suffix = vec![vec![0.0; len + 1]; k + 1];
+        suffix[k][len] = 1.0;
+        for i in (0..len).rev() {
+            suffix[k][i] = if on(i) { 0.0 } else { suffix[k][i + 1] };
+        }
+        for j in (0..k).rev() {
+            let hint = hints[j];
+            for i in (0..len).rev() {
+                let starts_empty = if on(i) { 0.0 } else { suffix[j][i + 1] };
+                let starts_with_block = if !placements.block_fits(i, hint) {
+                    0.0
+                } else if i + hint == len {
+                    (j + 1 == k) as usize as f64
+                } else if on(i + hint) {
+                    0.0
+                } else {
+                    suffix[j + 1][i + hint + 1]
+                };
+                suffix[j][i] = starts_empty + starts_with_block;
+            }
+        }
+
+        placements.prefix = prefix;
+        placements.suffix = suffix;
+        placements
+    }
+
+    fn block_fits(&self, start: usize, hint: usize) -> bool {
+        start + hint <= self.len && self.off_before[start + hint] == self.off_before[start]
+    }
+
+    // placements with block j starting at start
+    fn with_block(&self, hints: &[usize], j: usize, start: usize) -> f64 {
+        let hint = hints[j];
+        if !self.block_fits(start, hint) {
+            return 0.0;
+        }
+        let left = if start == 0 {
+            (j == 0) as usize as f64
+        } else if self.on[start - 1] {
+            0.0
+        } else {
+            self.prefix[j][start - 1]
+        };
+        let right = if start + hint == self.len {
+            (j + 1 == hints.len()) as usize as f64
+        } else if self.on[start + hint] {
+            0.0
+        } else {
+            self.suffix[j + 1][start + hint + 1]
+        };
+        left * right
+    }
+}
+
+// fixes every cell that has the same value in all placements of the hints
+fn settle_line<L: Line + ?Sized>(hints: &[usize], row: &mut L) -> Result<bool, Contradiction> {
+    let len = row.len();
+    let k = hints.len();
+    let placements = Placements::new(hints, row);
+    if placements.suffix[0][0] == 0.0 {
+        return Err(Contradiction);
+    }
+
+    let Placements { prefix, suffix, .. } = &placements;
+    let mut can_be_off = vec![false; len];
+    for (i, can_be_off) in can_be_off.iter_mut().enumerate() {
+        *can_be_off =
+            !placements.on[i] && (0..=k).any(|j| prefix[j][i] > 0.0 && suffix[j][i + 1] > 0.0);
+    }
+
+    // on_starts[i] - number of blocks that may cover cell i (as a difference array)
+    let mut on_starts = vec![0_i32; len + 1];
+    for (j, &hint) in hints.iter().enumerate() {
+        for start in 0..len {
+            if placements.with_block(hints, j, start) > 0.0 {
+                on_starts[start] += 1;
+                on_starts[start + hint] -= 1;
+            }
+        }
+    }
+
+    let mut changed = false;
+    let mut covering_blocks = 0;
+    for i in 0..len {
+        covering_blocks += on_starts[i];
+        let can_be_on = covering_blocks > 0;
+        let cell = match (can_be_on, can_be_off[i]) {
+            (true, true) => continue,
+            (true, false) => Cell::On,
+            (false, true) => Cell::Off,
+            (false, false) => return Err(Contradiction),
+        };
+        if row[i] != cell {
+            row[i] = cell;
+            changed = true;
+        }
+    }
+
+    Ok(changed)
+}
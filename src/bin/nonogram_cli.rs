@@ -0,0 +1,129 @@
+use std::env;
+use std::path::Path;
+use std::process;
+
+use nonogram_solver::analysis;
+use nonogram_solver::library::PuzzleFile;
+use nonogram_solver::render::{self, RenderOptions};
+use nonogram_solver::solver;
+
+const USAGE: &str = "Usage: nonogram_cli [options] <puzzle.json>
+  --png FILE         also draw the board to a PNG file
+  --cell-size PIXELS of the cells in the PNG, 16 by default
+  --steps N          stop after N steps of the line solver, to see how far it got
+  --playback FILE    save every step of the line solver as an animated GIF (FILE.gif)
+                     or as numbered PNG files (FILE-001.png, ...)
+  --frame-ms MS      how long a step of the GIF is shown, 500 by default";
+
+// Prints a puzzle file with its metadata and the solution:
+//   nonogram_cli puzzles/8x8.json
+//   nonogram_cli --steps 10 --png step10.png puzzles/20x20.json
+//   nonogram_cli --playback solving.gif puzzles/8x8.json
+fn main() {
+    let mut path = None;
+    let mut png_path = None;
+    let mut render_options = RenderOptions::default();
+    let mut steps = None;
+    let mut playback_path = None;
+    let mut frame_ms = 500;
+
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next()
+                .unwrap_or_else(|| fail(&format!("{} needs a value", arg)))
+        };
+        match arg.as_str() {
+            "--png" => png_path = Some(value()),
+            "--cell-size" => {
+                render_options.cell_size = value().parse().unwrap_or_else(|_| fail("bad size"));
+            }
+            "--steps" => steps = Some(value().parse().unwrap_or_else(|_| fail("bad steps"))),
+            "--playback" => playback_path = Some(value()),
+            "--frame-ms" => frame_ms = value().parse().unwrap_or_else(|_| fail("bad frame time")),
+            _ if arg.starts_with("--") || path.is_some() => {
+                fail(&format!("unexpected argument {}", arg))
+            }
+            _ => path = Some(arg),
+        }
+    }
+    let path = path.unwrap_or_else(|| fail("a puzzle file is needed"));
+
+    let PuzzleFile { info, mut board } = PuzzleFile::load(Path::new(&path)).unwrap_or_else(|err| {
+        eprintln!("Can't load {}: {}", path, err);
+        process::exit(1);
+    });
+
+    let fields = [
+        ("Title", info.title),
+        ("Author", info.author),
+        ("Date", info.date),
+        ("Uniqueness", info.uniqueness.map(|u| format!("{:?}", u))),
+        ("Difficulty", info.difficulty.map(|d| format!("{:?}", d))),
+    ];
+    for (label, value) in fields.iter() {
+        if let Some(value) = value {
+            println!("{}: {}", label, value);
+        }
+    }
+    println!("Size: {}x{}", board.rows, board.cols);
+
+    if let Some(playback_path) = playback_path {
+        let mut solved = board.clone();
+        let (_, trace) = solver::solve_with_trace(&mut solved, true);
+        let frames = render::render_trace(&board, &trace, &render_options);
+        let saved = match playback_path.strip_suffix(".png") {
+            _ if playback_path.ends_with(".gif") => {
+                render::save_gif(&frames, frame_ms, Path::new(&playback_path))
+                    .map(|_| vec![playback_path.clone()])
+            }
+            Some(prefix) => render::save_png_sequence(&frames, prefix),
+            None => render::save_png_sequence(&frames, &playback_path),
+        };
+        match saved {
+            Ok(files) => println!("Wrote {} frames to {}", frames.len(), files[0]),
+            Err(err) => {
+                eprintln!("Can't write {}: {}", playback_path, err);
+                process::exit(1);
+            }
+        }
+    }
+
+    match steps {
+        // the cells the solver found in its first steps
+        Some(steps) => {
+            let mut solved = board.clone();
+            let (_, trace) = solver::solve_with_trace(&mut solved, true);
+            board.clear_board();
+            for step in trace.iter().take(steps) {
+                for cell in step.cells.iter() {
+                    board.data[cell.row][cell.col] = cell.cell;
+                }
+            }
+            println!("After {} of {} steps", steps.min(trace.len()), trace.len());
+        }
+        None => {
+            let outcome = solver::solve(&mut board, true);
+            if !outcome.is_solved() {
+                analysis::complete_by_search(&mut board);
+            }
+        }
+    }
+    board.print_board();
+    if steps.is_none() && !solver::is_solved(&board) {
+        println!("No unique solution");
+    }
+
+    if let Some(png_path) = png_path {
+        let image = render::render_board(&board, &render_options);
+        if let Err(err) = image.save_png(Path::new(&png_path)) {
+            eprintln!("Can't write {}: {}", png_path, err);
+            process::exit(1);
+        }
+    }
+}
+
+fn fail(message: &str) -> ! {
+    eprintln!("{}\n\n{}", message, USAGE);
+    process::exit(2);
+}
@@ -0,0 +1,123 @@
+use std::env;
+use std::fs;
+use std::path::Path;
+use std::process;
+
+use nonogram_solver::analysis;
+use nonogram_solver::export::{self, ExportMode, PageOptions};
+use nonogram_solver::library::PuzzleFile;
+use nonogram_solver::solver;
+
+const USAGE: &str = "Usage: nonogram_export [options] <out.svg|out.pdf> <puzzle.json>...
+  --solution         print the solved grids instead of blank ones
+  --page SIZE        a4 (default), a5, letter or WIDTHxHEIGHT in points
+  --margin POINTS    around the page, 36 by default
+  --title TITLE      on top of every page
+  --author AUTHOR    under the title
+  --grid COLSxROWS   puzzles on a page, 1x1 by default
+
+An SVG file is written for each page, numbered after the first one if there are several.";
+
+// Renders puzzle files to print, e.g. a booklet of four blank puzzles a page:
+//   nonogram_export --grid 2x2 --title "Puzzles" booklet.pdf puzzles/*.json
+fn main() {
+    let mut options = PageOptions::default();
+    let mut paths = vec![];
+
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next()
+                .unwrap_or_else(|| fail(&format!("{} needs a value", arg)))
+        };
+        match arg.as_str() {
+            "--solution" => options.mode = ExportMode::Solution,
+            "--page" => {
+                let page = value();
+                options.size = match page.to_lowercase().as_str() {
+                    "a4" => export::A4,
+                    "a5" => export::A5,
+                    "letter" => export::LETTER,
+                    size => parse_pair(size).unwrap_or_else(|| fail("bad page size")),
+                }
+            }
+            "--margin" => {
+                options.margin = value().parse().unwrap_or_else(|_| fail("bad margin"));
+            }
+            "--title" => options.title = Some(value()),
+            "--author" => options.author = Some(value()),
+            "--grid" => {
+                let (columns, rows) = parse_pair(&value()).unwrap_or_else(|| fail("bad grid"));
+                options.columns = columns as usize;
+                options.rows = rows as usize;
+            }
+            _ if arg.starts_with("--") => fail(&format!("unknown option {}", arg)),
+            _ => paths.push(arg),
+        }
+    }
+    if paths.len() < 2 {
+        fail("an output file and at least one puzzle are needed");
+    }
+    let out = paths.remove(0);
+
+    let mut puzzles = vec![];
+    for path in paths.iter() {
+        let PuzzleFile { info, mut board } =
+            PuzzleFile::load(Path::new(path)).unwrap_or_else(|err| {
+                eprintln!("Can't load {}: {}", path, err);
+                process::exit(1);
+            });
+        if options.mode == ExportMode::Solution {
+            board.clear_board();
+            if !solver::solve(&mut board, true).is_solved() {
+                analysis::complete_by_search(&mut board);
+            }
+            if !solver::is_solved(&board) {
+                eprintln!("{} has no unique solution, printing what is certain", path);
+            }
+        }
+        let caption = info.title.unwrap_or_else(|| {
+            let stem = Path::new(path).file_stem().and_then(|s| s.to_str());
+            stem.unwrap_or_default().to_owned()
+        });
+        puzzles.push((caption, board));
+    }
+    let puzzles = puzzles
+        .iter()
+        .map(|(caption, board)| (caption.as_str(), board))
+        .collect::<Vec<_>>();
+
+    let written = if out.ends_with(".pdf") {
+        fs::write(&out, export::to_pdf(&puzzles, &options)).map(|_| vec![out.clone()])
+    } else {
+        let pages = export::to_svg(&puzzles, &options);
+        let stem = out.strip_suffix(".svg").unwrap_or(&out);
+        let names = (1..=pages.len()).map(|n| match n {
+            1 => format!("{}.svg", stem),
+            _ => format!("{}-{}.svg", stem, n),
+        });
+        names
+            .zip(pages)
+            .map(|(name, page)| fs::write(&name, page).map(|_| name))
+            .collect()
+    };
+
+    match written {
+        Ok(files) => println!("Wrote {}", files.join(", ")),
+        Err(err) => {
+            eprintln!("Can't write {}: {}", out, err);
+            process::exit(1);
+        }
+    }
+}
+
+// "2x3" as (2.0, 3.0)
+fn parse_pair(s: &str) -> Option<(f32, f32)> {
+    let (a, b) = s.split_once('x')?;
+    Some((a.parse().ok()?, b.parse().ok()?))
+}
+
+fn fail(message: &str) -> ! {
+    eprintln!("{}\n\n{}", message, USAGE);
+    process::exit(2);
+}
@@ -0,0 +1,18 @@
+use std::env;
+use std::process;
+
+use nonogram_solver::server;
+
+fn main() {
+    let addr = env::args()
+        .nth(1)
+        .unwrap_or_else(|| "127.0.0.1:8080".to_owned());
+
+    let server = server::bind(addr.as_str()).unwrap_or_else(|err| {
+        eprintln!("Can't listen on {}: {}", addr, err);
+        process::exit(1);
+    });
+
+    println!("Listening on http://{}", addr);
+    server::serve(&server);
+}
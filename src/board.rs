@@ -1,7 +1,19 @@
-use macroquad::color::{BLUE, GRAY, GREEN, SKYBLUE};
-use macroquad::math::{bool, f32, i32};
-use macroquad::prelude::{draw_line, draw_rectangle, draw_text, screen_height, screen_width};
+use std::fmt;
+
 use itertools::Itertools;
+#[cfg(feature = "gui")]
+use macroquad::color::{BLUE, GRAY, GREEN, SKYBLUE};
+#[cfg(feature = "gui")]
+use macroquad::prelude::{
+    draw_line, draw_rectangle, draw_text, measure_text, screen_height, screen_width,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::json::BoardJson;
+use crate::line::{Line, LineMut};
+
+// thicker lines between the groups of this many rows and cols make counting cells easier
+pub const GUIDE_EVERY: usize = 5;
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
 pub enum Cell {
@@ -36,7 +48,7 @@ impl Cell {
     }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Rule {
     pub n: usize,
     pub is_col: bool,
@@ -49,40 +61,273 @@ impl Rule {
     }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LineId {
+    Row(usize),
+    Col(usize),
+}
+
+impl LineId {
+    pub fn of_rule(rule: &Rule) -> Self {
+        if rule.is_col {
+            LineId::Col(rule.n)
+        } else {
+            LineId::Row(rule.n)
+        }
+    }
+
+    pub fn n(&self) -> usize {
+        match *self {
+            LineId::Row(n) | LineId::Col(n) => n,
+        }
+    }
+
+    pub fn is_col(&self) -> bool {
+        matches!(self, LineId::Col(_))
+    }
+}
+
+impl fmt::Display for LineId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LineId::Row(n) => write!(f, "row {}", n),
+            LineId::Col(n) => write!(f, "col {}", n),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ClueErrorKind {
+    MissingLine,    // no rule for a row or col (`Board::from_rules`)
+    DuplicateLine,  // more than one rule for a row or col (`Board::from_rules`)
+    LineOutOfRange, // rule for a row or col the board doesn't have (`Board::from_rules`)
+    ZeroHint,
+    HintsTooLong,   // hints and the gaps between them don't fit into the line
+    TotalsMismatch, // rows and cols disagree on the number of painted cells
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct ClueError {
+    pub line: Option<LineId>, // None if the error is about the whole board
+    pub kind: ClueErrorKind,
+    pub details: String,
+}
+
+impl fmt::Display for ClueError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.line {
+            Some(line) => write!(f, "{}: {}", line, self.details),
+            None => write!(f, "{}", self.details),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Clue {
+    pub hints: Vec<usize>,
+}
+
+impl Clue {
+    pub fn new(hints: Vec<usize>) -> Self {
+        Clue { hints }
+    }
+}
+
+impl From<Vec<usize>> for Clue {
+    fn from(hints: Vec<usize>) -> Self {
+        Clue::new(hints)
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(into = "BoardJson", try_from = "BoardJson")]
 pub struct Board {
     pub rows: usize,
     pub cols: usize,
     pub data: Vec<Vec<Cell>>,
-    pub rules: Vec<Rule>,
+    row_clues: Vec<Clue>, // one per row, top to bottom
+    col_clues: Vec<Clue>, // one per col, left to right
 }
 
 impl Board {
-    pub fn new(rows: usize, cols: usize, rules: Vec<Rule>) -> Self {
+    // a blank board without hints
+    pub fn new(rows: usize, cols: usize) -> Self {
+        Board::with_clues(vec![Clue::default(); rows], vec![Clue::default(); cols])
+    }
+
+    pub fn with_clues(row_clues: Vec<Clue>, col_clues: Vec<Clue>) -> Self {
+        let (rows, cols) = (row_clues.len(), col_clues.len());
         Board {
             rows,
             cols,
             data: vec![vec![Cell::None; cols]; rows],
-            rules,
+            row_clues,
+            col_clues,
         }
     }
 
-    pub fn get_row(&mut self, n: usize) -> Vec<&mut Cell> {
-        self.data.get_mut(n).unwrap().iter_mut().collect()
+    // for the old flat list of rules, every line needs exactly one rule
+    pub fn from_rules(rows: usize, cols: usize, rules: Vec<Rule>) -> Result<Self, Vec<ClueError>> {
+        let mut board = Board::new(rows, cols);
+        let mut errors = vec![];
+        let mut rules_per_line = [vec![0; rows], vec![0; cols]];
+
+        for rule in rules {
+            let line = LineId::of_rule(&rule);
+            let lines = &mut rules_per_line[rule.is_col as usize];
+            match lines.get_mut(rule.n) {
+                Some(count) => *count += 1,
+                None => {
+                    errors.push(ClueError {
+                        line: Some(line),
+                        kind: ClueErrorKind::LineOutOfRange,
+                        details: format!("the board has only {} of these lines", lines.len()),
+                    });
+                    continue;
+                }
+            }
+            board.set_clue(line, Clue::new(rule.hints));
+        }
+
+        for (is_col, lines) in [false, true].iter().zip(rules_per_line.iter()) {
+            for (n, &count) in lines.iter().enumerate() {
+                let line = if *is_col { LineId::Col(n) } else { LineId::Row(n) };
+                if count == 0 {
+                    errors.push(ClueError {
+                        line: Some(line),
+                        kind: ClueErrorKind::MissingLine,
+                        details: "no rule for this line".to_owned(),
+                    });
+                } else if count > 1 {
+                    errors.push(ClueError {
+                        line: Some(line),
+                        kind: ClueErrorKind::DuplicateLine,
+                        details: format!("{} rules for this line", count),
+                    });
+                }
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(board)
+        } else {
+            Err(errors)
+        }
     }
 
-    pub fn get_col(&mut self, n: usize) -> Vec<&mut Cell> {
-        self.data
-            .iter_mut()
-            .map(|row| row.get_mut(n).unwrap())
+    // rows first, then cols
+    pub fn rules(&self) -> Vec<Rule> {
+        self.lines()
+            .map(|(line, clue)| Rule::new(line.n(), line.is_col(), clue.hints.clone()))
             .collect()
     }
 
+    pub fn row_clues(&self) -> &[Clue] {
+        &self.row_clues
+    }
+
+    pub fn col_clues(&self) -> &[Clue] {
+        &self.col_clues
+    }
+
+    pub fn clue(&self, line: LineId) -> &Clue {
+        match line {
+            LineId::Row(n) => &self.row_clues[n],
+            LineId::Col(n) => &self.col_clues[n],
+        }
+    }
+
+    pub fn set_clue(&mut self, line: LineId, clue: Clue) {
+        match line {
+            LineId::Row(n) => self.row_clues[n] = clue,
+            LineId::Col(n) => self.col_clues[n] = clue,
+        }
+    }
+
+    // takes the clues of a board of the same size
+    pub fn copy_clues(&mut self, board: &Board) {
+        assert_eq!((self.rows, self.cols), (board.rows, board.cols));
+        self.row_clues = board.row_clues.clone();
+        self.col_clues = board.col_clues.clone();
+    }
+
+    // every row and then every col with its clue
+    pub fn lines(&self) -> impl Iterator<Item = (LineId, &Clue)> {
+        let rows = self.row_clues.iter().enumerate().map(|(n, c)| (LineId::Row(n), c));
+        let cols = self.col_clues.iter().enumerate().map(|(n, c)| (LineId::Col(n), c));
+        rows.chain(cols)
+    }
+
+    pub fn line_len(&self, line: LineId) -> usize {
+        match line {
+            LineId::Row(_) => self.cols,
+            LineId::Col(_) => self.rows,
+        }
+    }
+
+    // checks that every clue can be satisfied on its own and rows agree with cols
+    pub fn validate(&self) -> Vec<ClueError> {
+        let mut errors = vec![];
+
+        for (line, clue) in self.lines() {
+            if clue.hints.contains(&0) {
+                errors.push(ClueError {
+                    line: Some(line),
+                    kind: ClueErrorKind::ZeroHint,
+                    details: format!("hints {:?} contain 0", clue.hints),
+                });
+            }
+
+            let length = self.line_len(line);
+            let min_length = clue.hints.iter().sum::<usize>() + clue.hints.len().saturating_sub(1);
+            if min_length > length {
+                errors.push(ClueError {
+                    line: Some(line),
+                    kind: ClueErrorKind::HintsTooLong,
+                    details: format!(
+                        "hints {:?} need {} cells but the line has {}",
+                        clue.hints, min_length, length
+                    ),
+                });
+            }
+        }
+
+        let total = |clues: &[Clue]| clues.iter().flat_map(|c| c.hints.iter()).sum::<usize>();
+        let (row_total, col_total) = (total(&self.row_clues), total(&self.col_clues));
+        if errors.is_empty() && row_total != col_total {
+            errors.push(ClueError {
+                line: None,
+                kind: ClueErrorKind::TotalsMismatch,
+                details: format!(
+                    "row hints add up to {} but col hints to {}",
+                    row_total, col_total
+                ),
+            });
+        }
+
+        errors
+    }
+
+    pub fn get_line(&mut self, line: LineId) -> LineMut<'_> {
+        LineMut::new(&mut self.data, line)
+    }
+
+    pub fn get_row(&mut self, n: usize) -> &mut [Cell] {
+        &mut self.data[n]
+    }
+
+    pub fn get_col(&mut self, n: usize) -> LineMut<'_> {
+        self.get_line(LineId::Col(n))
+    }
+
     pub fn boards_are_equal(&self, board: &Board) -> bool {
         if self.rows != board.rows || self.cols != board.cols { return false; }
 
         for n_row in 0..self.rows {
-            for n_col in 0..self.rows {
+            for n_col in 0..self.cols {
                 if self.data[n_row][n_col] == Cell::On && board.data[n_row][n_col] != Cell::On {
                     return false
                 }
@@ -95,83 +340,106 @@ impl Board {
         true
     }
 
+    // whether the painted cells give exactly the clues of the board
+    pub fn satisfies_clues(&self) -> bool {
+        let mut painted = self.clone();
+        painted.generate_clues_from_board();
+        painted.row_clues == self.row_clues && painted.col_clues == self.col_clues
+    }
+
     pub fn print_board(&self) {
-        let max_hints = [|r: &Rule| !r.is_col, |r: &Rule| r.is_col]
+        for line in self.format_board(GUIDE_EVERY) {
+            println!("{}", line);
+        }
+    }
+
+    // the clues and the cells as text, with guide lines between every `guide_every` rows and
+    // cols (none if it is 0)
+    pub fn format_board(&self, guide_every: usize) -> Vec<String> {
+        let max_hints = [&self.row_clues, &self.col_clues]
             .iter()
-            .map(|f| {
-                self.rules
-                    .iter()
-                    .filter_map(|r| f(r).then(|| r.hints.len()))
-                    .max()
-            })
-            .flatten()
+            .map(|clues| clues.iter().map(|c| c.hints.len()).max().unwrap_or(0))
             .collect_vec();
 
         let (max_row_hints, max_col_hints) = (max_hints[0], max_hints[1]);
-        dbg!(max_row_hints, max_col_hints);
-
-        let row_hints = self.rules.iter().filter(|r| !r.is_col).collect::<Vec<_>>();
-        let col_hints = self.rules.iter().filter(|r| r.is_col).collect::<Vec<_>>();
 
-        // print col hints
-        let mut str_rows: Vec<String> = vec![];
-        let row_padding = max_row_hints + 1;
-        for row in 0..max_col_hints {
-            let mut s = String::with_capacity(row_padding);
-            for _ in 0..row_padding {
-                s.push(' ');
+        let (row_hints, col_hints) = (&self.row_clues, &self.col_clues);
+
+        // hints of more than one digit get a space in front, so neighbours don't run together
+        let slot_width = |clues: &[Clue]| {
+            let digits = clues
+                .iter()
+                .flat_map(|c| c.hints.iter())
+                .map(|hint| hint.to_string().len())
+                .max()
+                .unwrap_or(1);
+            if digits > 1 {
+                digits + 1
+            } else {
+                1
             }
+        };
+        let (row_width, col_width) = (slot_width(row_hints), slot_width(col_hints));
+        let hint_slot = |hint: Option<&usize>, width: usize| match hint {
+            Some(hint) => format!("{:>width$}", hint, width = width),
+            None => " ".repeat(width),
+        };
+
+        // a row of the grid with `guide` between the groups of cols
+        let is_guide = |n: usize| guide_every > 0 && n > 0 && n.is_multiple_of(guide_every);
+        let grid_row = |cell: &dyn Fn(usize) -> String, guide: char| {
+            let mut s = String::with_capacity(self.cols * (col_width + 1));
             for col in 0..self.cols {
-                if let Some(hint) = col_hints[col].hints.get(row) {
-                    s.push(hint.to_string().chars().next().unwrap());
-                } else {
-                    s.push(' ');
+                if is_guide(col) {
+                    s.push(guide);
                 }
+                s.push_str(&cell(col));
             }
-            str_rows.push(s);
-        }
-        let mut row_edge = String::new();
-        for row in 0..self.cols + row_padding {
-            row_edge.push(if row < row_padding { ' ' } else { '_' });
+            s
+        };
+        let filled = |ch: char| ch.to_string().repeat(col_width);
+
+        // print col hints
+        let mut str_rows: Vec<String> = vec![];
+        let row_padding = " ".repeat(max_row_hints * row_width + 1);
+        for row in 0..max_col_hints {
+            let hints = grid_row(&|col| hint_slot(col_hints[col].hints.get(row), col_width), ' ');
+            str_rows.push(format!("{}{}", row_padding, hints));
         }
-        str_rows.push(row_edge);
+        str_rows.push(format!("{}{}", row_padding, grid_row(&|_| filled('_'), '_')));
 
         // print board and row hints
-        for row in 0..self.rows {
-            let mut s = String::with_capacity(row_padding);
-            for col in 0..max_row_hints {
-                if let Some(hint) = row_hints[row].hints.get(col) {
-                    s.push(hint.to_string().chars().next().unwrap());
-                } else {
-                    s.push(' ');
-                }
+        for (row, (clue, data)) in row_hints.iter().zip(self.data.iter()).enumerate() {
+            if is_guide(row) {
+                let guide = grid_row(&|_| filled('-'), '+');
+                str_rows.push(format!("{}|{}", " ".repeat(max_row_hints * row_width), guide));
             }
 
-            s.push('|');
-
-            for cell in self.data[row].iter() {
-                s.push(cell.as_char());
-            }
-
-            str_rows.push(s);
+            let hints = (0..max_row_hints)
+                .map(|col| hint_slot(clue.hints.get(col), row_width))
+                .collect::<String>();
+            let cells = grid_row(&|col| filled(data[col].as_char()), '|');
+            str_rows.push(format!("{}|{}", hints, cells));
         }
 
-        for row in str_rows.iter() {
-            println!("{}", row);
-        }
+        str_rows
     }
 
-    pub fn get_consecutive_regions(
-        row: &Vec<&mut Cell>, include_none_cells: bool, incl_interval: Option<(usize, usize)>,
+    pub fn get_consecutive_regions<L: Line + ?Sized>(
+        row: &L, include_none_cells: bool, incl_interval: Option<(usize, usize)>,
     ) -> Vec<(usize, usize)> {
-        let (start, end) = incl_interval.unwrap_or((0, row.len() - 1));
+        // the interval may reach past the line, only the cells inside are looked at
+        let (start, end) = match incl_interval {
+            Some((start, end)) => (start, end.saturating_add(1).min(row.len())),
+            None => (0, row.len()),
+        };
 
         let (mut pos, mut length) = (0, 0);
 
         let mut regions = vec![];
-        for i in start..=end {
+        for i in start..end {
             let cell = &row[i];
-            if cell == &&Cell::On || (include_none_cells && cell == &&Cell::None) {
+            if cell == &Cell::On || (include_none_cells && cell == &Cell::None) {
                 if length == 0 {
                     pos = i;
                 }
@@ -188,10 +456,10 @@ impl Board {
         regions
     }
 
-    pub fn count_block_length(row: &mut Vec<&mut Cell>, pos: usize, count_backward: bool) -> usize {
+    pub fn count_block_length<L: Line + ?Sized>(row: &L, pos: usize, count_backward: bool) -> usize {
         let mut length = 0;
         if count_backward {
-            while let Some(Cell::On) = row.get(pos - length) {
+            while let Some(Cell::On) = pos.checked_sub(length).and_then(|i| row.get(i)) {
                 length += 1;
             }
         } else {
@@ -200,21 +468,21 @@ impl Board {
             }
         }
 
-        return length;
+        length
     }
 
-    pub fn find_first_cell_such_that(
-        row: &mut Vec<&mut Cell>, f: fn(&Cell) -> bool, reverse: bool,
+    pub fn find_first_cell_such_that<L: Line + ?Sized>(
+        row: &L, f: fn(&Cell) -> bool, reverse: bool,
     ) -> Option<usize> {
         if reverse {
             for i in (0..row.len()).rev() {
-                if f(row[i]) {
+                if f(&row[i]) {
                     return Some(i);
                 }
             }
         } else {
             for i in 0..row.len() {
-                if f(row[i]) {
+                if f(&row[i]) {
                     return Some(i);
                 }
             }
@@ -227,100 +495,73 @@ impl Board {
         self.data = vec![vec![Cell::None; self.cols]; self.rows];
     }
 
-    pub fn generate_new_rules_according_to_board(&mut self) -> Vec<Rule> {
-        let mut rules = vec![];
-
-        // rows
-        for n_row in 0..self.rows {
-            let row = self.get_row(n_row);
-            let regions = Self::get_consecutive_regions(&row, false, None);
-            let rule = Rule::new(
-                n_row,
-                false,
-                regions.iter().map(|&r| r.1).collect(),
-            );
-            rules.push(rule);
-        }
-
-        // cols
-        for n_col in 0..self.rows {
-            let row = self.get_col(n_col);
-            let regions = Self::get_consecutive_regions(&row, false, None);
-            let rule = Rule::new(n_col, true, regions.iter().map(|&r| r.1).collect());
-            rules.push(rule);
-        }
-
-        rules
+    // replaces the clues with the ones matching the painted cells
+    pub fn generate_clues_from_board(&mut self) {
+        let (rows, cols) = (self.rows, self.cols);
+        let mut clue_of = |line| {
+            let col_or_row = self.get_line(line);
+            let regions = Self::get_consecutive_regions(&col_or_row, false, None);
+            Clue::new(regions.iter().map(|&r| r.1).collect())
+        };
+
+        let row_clues = (0..rows).map(|n| clue_of(LineId::Row(n))).collect();
+        let col_clues = (0..cols).map(|n| clue_of(LineId::Col(n))).collect();
+        self.row_clues = row_clues;
+        self.col_clues = col_clues;
     }
 
+    #[cfg(feature = "gui")]
     pub fn draw_board(&self) {
-        let row_hints = self.rules.iter().filter(|r| !r.is_col).collect::<Vec<_>>();
-        let col_hints = self.rules.iter().filter(|r| r.is_col).collect::<Vec<_>>();
+        let (row_hints, col_hints) = (&self.row_clues, &self.col_clues);
 
-        let max_hints = [&row_hints, &col_hints]
+        let max_hints = [row_hints, col_hints]
             .iter()
-            .map(|hints| hints.iter().map(|r| r.hints.len()).max())
-            .flatten()
+            .map(|clues| clues.iter().map(|c| c.hints.len()).max().unwrap_or(0))
             .collect::<Vec<_>>();
         let (max_row_hints, max_col_hints) = (max_hints[0], max_hints[1]);
 
-        // print col hints
-        let mut str_rows: Vec<String> = vec![];
-        let row_padding = max_row_hints;
-        for row in 0..max_col_hints {
-            let mut s = String::with_capacity(row_padding);
-            for _ in 0..row_padding {
-                s.push(' ');
+        // one square for each hint and each cell
+        let rect_size = (screen_width() / (max_row_hints + self.cols) as f32)
+            .min(screen_height() / (max_col_hints + self.rows) as f32);
+        let size_offset = 0.05 * rect_size;
+        let size = rect_size - size_offset;
+        let square = |n_row: usize, n_col: usize| {
+            (
+                rect_size * n_col as f32 + size_offset,
+                rect_size * n_row as f32 + size_offset,
+            )
+        };
+
+        // the numbers shrink until they fit their square
+        let draw_hint = |hint: usize, n_row: usize, n_col: usize| {
+            let (x, y) = square(n_row, n_col);
+            let text = hint.to_string();
+            let width = measure_text(&text, None, size as u16, 1.0).width;
+            let font_size = size.min(size * size * 0.9 / width.max(1.0));
+            let width = measure_text(&text, None, font_size as u16, 1.0).width;
+            let text_y = y + (size + font_size * 0.6) / 2.0;
+            draw_text(&text, x + (size - width) / 2.0, text_y, font_size, BLUE);
+        };
+
+        // col hints above the grid, row hints left of it
+        for (col, clue) in col_hints.iter().enumerate() {
+            for (row, &hint) in clue.hints.iter().enumerate() {
+                draw_hint(hint, row, max_row_hints + col);
             }
-            for col in 0..self.cols {
-                if let Some(hint) = col_hints[col].hints.get(row) {
-                    s.push(*hint as u8 as char);
-                } else {
-                    s.push(' ');
-                }
-            }
-            str_rows.push(s);
         }
-
-        // print board and row hints
-        for row in 0..self.rows {
-            let mut s = String::with_capacity(row_padding);
-            for col in 0..max_row_hints {
-                if let Some(hint) = row_hints[row].hints.get(col) {
-                    s.push(*hint as u8 as char);
-                } else {
-                    s.push(' ');
-                }
+        for (row, clue) in row_hints.iter().enumerate() {
+            for (col, &hint) in clue.hints.iter().enumerate() {
+                draw_hint(hint, max_col_hints + row, col);
             }
-
-            for cell in self.data[row].iter() {
-                s.push(cell.as_char());
-            }
-
-            str_rows.push(s);
         }
 
-        let screen_size = screen_width().min(screen_height());
-        let rect_size = screen_size / str_rows.len() as f32;
-
-        for (n_row, row) in str_rows.iter().enumerate() {
-            for (n_col, ch) in row.char_indices() {
-                let size_offset = 0.05 * rect_size;
-                let x = rect_size * n_col as f32 + size_offset;
-                let y = rect_size * n_row as f32 + size_offset;
-                let text_y = rect_size * (n_row as f32 + 0.5) + size_offset;
-                let size = rect_size - size_offset;
-                if ch == Cell::On.as_char() {
-                    draw_rectangle(x, y, size, size, GREEN);
-                } else if ch == Cell::Off.as_char() {
-                    draw_rectangle(x, y, size, size, GRAY);
-                } else if ch == Cell::None.as_char()
-                    && n_row >= max_col_hints
-                    && n_col >= max_row_hints
-                {
-                    // draw_rectangle(x, y, size, size, DARKGRAY);
-                } else if (ch as u8) < 30 {
-                    draw_text(&(ch as u8).to_string(), x, text_y, size, BLUE);
+        for (n_row, row) in self.data.iter().enumerate() {
+            for (n_col, cell) in row.iter().enumerate() {
+                let (x, y) = square(max_col_hints + n_row, max_row_hints + n_col);
+                match cell {
+                    Cell::On => draw_rectangle(x, y, size, size, GREEN),
+                    Cell::Off => draw_rectangle(x, y, size, size, GRAY),
+                    Cell::None => {}
                 }
             }
         }
@@ -0,0 +1,201 @@
+use std::ops::Range;
+
+use macroquad::prelude::*;
+
+use nonogram_solver::board::{Board, Clue};
+
+pub const HEADER_HEIGHT: f32 = 60.0; // the text about the board, above the col clues
+const MIN_CELL_SIZE: f32 = 4.0;
+const MAX_CELL_SIZE: f32 = 120.0;
+const ZOOM_STEP: f32 = 1.15;
+const MIN_VISIBLE_CELLS: usize = 2; // next to the clues, however far the board is zoomed in
+
+// Zoom and scroll of the grid. The row clues stay pinned to the left edge of the window and the
+// col clues to the top (below the header), they only move along with the grid.
+// Mouse wheel zooms around the pointer, dragging with the middle button pans, F fits the board
+// into the window again.
+pub struct Camera {
+    cell_size: f32,
+    scroll: Vec2, // how far the grid is moved from its place next to the clues
+    fitted: bool, // follows the window size until zoomed or panned
+    drag_from: Option<Vec2>,
+    pub labels: bool, // room for the row and col numbers beyond the clues
+}
+
+// where everything is on screen this frame
+pub struct Layout {
+    pub cell_size: f32,
+    pub grid: Vec2, // top left corner of the grid
+    pub row_clues_width: f32,
+    pub col_clues_bottom: f32,
+}
+
+impl Layout {
+    pub fn cell_pos(&self, n_row: usize, n_col: usize) -> Vec2 {
+        self.grid + vec2(n_col as f32, n_row as f32) * self.cell_size
+    }
+
+    // the rows and cols at least partly in the window and not under the clues
+    pub fn visible_cells(&self, board: &Board) -> (Range<usize>, Range<usize>) {
+        let range = |from: f32, to: f32, grid: f32, count: usize| {
+            let first = ((from - grid) / self.cell_size).floor().max(0.0) as usize;
+            let last = ((to - grid) / self.cell_size).ceil().max(0.0) as usize;
+            first.min(count)..last.min(count)
+        };
+        (
+            range(
+                self.col_clues_bottom,
+                screen_height(),
+                self.grid.y,
+                board.rows,
+            ),
+            range(
+                self.row_clues_width,
+                screen_width(),
+                self.grid.x,
+                board.cols,
+            ),
+        )
+    }
+}
+
+impl Camera {
+    pub fn new() -> Self {
+        Camera {
+            cell_size: MIN_CELL_SIZE,
+            scroll: Vec2::ZERO,
+            fitted: true,
+            drag_from: None,
+            labels: false,
+        }
+    }
+
+    pub fn update(&mut self, board: &Board) {
+        if is_key_pressed(KeyCode::F) {
+            self.fitted = true;
+        }
+        if self.fitted {
+            self.fit(board);
+        }
+
+        let mouse = Vec2::from(mouse_position());
+        let wheel = mouse_wheel().1;
+        if wheel != 0.0 {
+            // keep the point under the mouse where it is
+            let layout = self.layout(board);
+            let grid_point = (mouse - layout.grid) / self.cell_size;
+            let zoom = if wheel > 0.0 {
+                ZOOM_STEP
+            } else {
+                1.0 / ZOOM_STEP
+            };
+            let max_cell_size = self.max_cell_size(board);
+            self.cell_size = (self.cell_size * zoom).clamp(MIN_CELL_SIZE, max_cell_size);
+
+            let home = self.layout(board).grid - self.scroll;
+            self.scroll = mouse - grid_point * self.cell_size - home;
+            self.fitted = false;
+        }
+
+        if is_mouse_button_pressed(MouseButton::Middle) {
+            self.drag_from = Some(mouse);
+        } else if !is_mouse_button_down(MouseButton::Middle) {
+            self.drag_from = None;
+        }
+        if let Some(drag_from) = self.drag_from {
+            self.scroll += mouse - drag_from;
+            self.drag_from = Some(mouse);
+            self.fitted = false;
+        }
+
+        // the window may have shrunk or the labels taken more room
+        self.cell_size = self.cell_size.min(self.max_cell_size(board));
+        self.clamp_scroll(board);
+    }
+
+    // the whole board with its clues in the window
+    pub fn fit(&mut self, board: &Board) {
+        let (row_hints, col_hints) = self.clue_cells(board);
+        let width = screen_width() / (board.cols + row_hints) as f32;
+        let height = (screen_height() - HEADER_HEIGHT) / (board.rows + col_hints) as f32;
+        self.cell_size = width.min(height).clamp(MIN_CELL_SIZE, MAX_CELL_SIZE);
+        self.scroll = Vec2::ZERO;
+    }
+
+    pub fn layout(&self, board: &Board) -> Layout {
+        let (row_hints, col_hints) = self.clue_cells(board);
+        let row_clues_width = row_hints as f32 * self.cell_size;
+        let col_clues_bottom = HEADER_HEIGHT + col_hints as f32 * self.cell_size;
+        Layout {
+            cell_size: self.cell_size,
+            grid: vec2(row_clues_width, col_clues_bottom) + self.scroll,
+            row_clues_width,
+            col_clues_bottom,
+        }
+    }
+
+    // the cell under a point of the window, not if the point is over the clues
+    pub fn cell_at(&self, board: &Board, point: Vec2) -> Option<(usize, usize)> {
+        let layout = self.layout(board);
+        if point.x < layout.row_clues_width || point.y < layout.col_clues_bottom {
+            return None;
+        }
+
+        let cell = (point - layout.grid) / layout.cell_size;
+        let (n_row, n_col) = (cell.y.floor(), cell.x.floor());
+        if n_row < 0.0 || n_col < 0.0 || n_row >= board.rows as f32 || n_col >= board.cols as f32 {
+            return None;
+        }
+        Some((n_row as usize, n_col as usize))
+    }
+
+    // scrolls just enough for the cell to be out from under the clues and in the window
+    pub fn show_cell(&mut self, board: &Board, n_row: usize, n_col: usize) {
+        let layout = self.layout(board);
+        let pos = layout.cell_pos(n_row, n_col);
+        let top_left = vec2(layout.row_clues_width, layout.col_clues_bottom);
+        let bottom_right = vec2(screen_width(), screen_height()) - Vec2::splat(self.cell_size);
+        self.scroll += (top_left - pos).max(Vec2::ZERO) + (bottom_right - pos).min(Vec2::ZERO);
+        self.clamp_scroll(board);
+    }
+
+    // the clues may not take the whole window, the grid couldn't be clicked then
+    fn max_cell_size(&self, board: &Board) -> f32 {
+        let (row_hints, col_hints) = self.clue_cells(board);
+        let width = screen_width() / (row_hints + MIN_VISIBLE_CELLS) as f32;
+        let height = (screen_height() - HEADER_HEIGHT) / (col_hints + MIN_VISIBLE_CELLS) as f32;
+        width.min(height).clamp(MIN_CELL_SIZE, MAX_CELL_SIZE)
+    }
+
+    // the cells kept left of and above the grid
+    fn clue_cells(&self, board: &Board) -> (usize, usize) {
+        let (row_hints, col_hints) = hint_counts(board);
+        let labels = self.labels as usize;
+        (row_hints + labels, col_hints + labels)
+    }
+
+    // the grid may be moved until its far edge reaches the clues, but not away from them
+    fn clamp_scroll(&mut self, board: &Board) {
+        let layout = self.layout(board);
+        let grid_size = vec2(board.cols as f32, board.rows as f32) * self.cell_size;
+        let view = vec2(
+            screen_width() - layout.row_clues_width,
+            screen_height() - layout.col_clues_bottom,
+        );
+        let min = (view - grid_size).min(Vec2::ZERO);
+        self.scroll = self.scroll.max(min).min(Vec2::ZERO);
+    }
+}
+
+// how many hints the longest row and col clues have, at least one cell is kept for the clues
+pub fn hint_counts(board: &Board) -> (usize, usize) {
+    let longest = |clues: &[Clue]| {
+        clues
+            .iter()
+            .map(|c| c.hints.len())
+            .max()
+            .unwrap_or(0)
+            .max(1)
+    };
+    (longest(board.row_clues()), longest(board.col_clues()))
+}
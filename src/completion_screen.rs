@@ -0,0 +1,124 @@
+use macroquad::prelude::*;
+
+use nonogram_solver::board::Board;
+
+use crate::library_screen::draw_thumbnail;
+
+const WIDTH: f32 = 480.0;
+const HEIGHT: f32 = 320.0;
+const PICTURE_SIZE: f32 = 170.0;
+
+pub enum CompletionChoice {
+    Next,
+    Back, // to the board, for puzzles that aren't in the library
+    Library,
+}
+
+// Shown over the board once a puzzle is solved: the finished picture, how the game went and the
+// best time. Enter moves on to the next puzzle of the library, Escape goes back to the library.
+pub struct CompletionScreen {
+    pub picture: Board,
+    pub elapsed_secs: f64,
+    pub moves: usize,
+    pub mistakes: usize,
+    pub hints: usize,
+    pub best_secs: Option<f64>, // before this game
+    pub new_best: bool,
+    pub has_next: bool,
+    pub in_library: bool,
+}
+
+impl CompletionScreen {
+    pub fn update(&self) -> Option<CompletionChoice> {
+        let (next_button, library_button) = buttons();
+        let mouse = Vec2::from(mouse_position());
+        let clicked =
+            |button: Rect| is_mouse_button_pressed(MouseButton::Left) && button.contains(mouse);
+
+        if is_key_pressed(KeyCode::Enter) || clicked(next_button) {
+            Some(self.first_choice())
+        } else if is_key_pressed(KeyCode::Escape) || clicked(library_button) {
+            Some(CompletionChoice::Library)
+        } else {
+            None
+        }
+    }
+
+    pub fn draw(&self) {
+        let (x, y) = panel_pos();
+        draw_rectangle(x, y, WIDTH, HEIGHT, BLACK);
+        draw_rectangle_lines(x, y, WIDTH, HEIGHT, 2.0, SKYBLUE);
+        draw_text("Solved!", x + 20.0, y + 45.0, 40.0, GREEN);
+        draw_thumbnail(&self.picture, x + 20.0, y + 65.0, PICTURE_SIZE);
+
+        let best = match self.best_secs {
+            _ if self.new_best => ("New best time!".to_owned(), YELLOW),
+            Some(best) => (format!("Best: {}", format_time(best)), WHITE),
+            None => (String::new(), WHITE),
+        };
+        let lines = [
+            (format!("Time: {}", format_time(self.elapsed_secs)), WHITE),
+            best,
+            (format!("Moves: {}", self.moves), WHITE),
+            (format!("Mistakes: {}", self.mistakes), WHITE),
+            (format!("Hints: {}", self.hints), WHITE),
+        ];
+        let text_x = x + PICTURE_SIZE + 50.0;
+        for (i, (line, color)) in lines.iter().enumerate() {
+            draw_text(line, text_x, y + 90.0 + i as f32 * 32.0, 28.0, *color);
+        }
+
+        let first = match self.first_choice() {
+            CompletionChoice::Next => "Next puzzle",
+            _ => "Back",
+        };
+        let (next_button, library_button) = buttons();
+        for (button, label) in [(next_button, first), (library_button, "Library")].iter() {
+            draw_rectangle_lines(button.x, button.y, button.w, button.h, 1.0, SKYBLUE);
+            draw_text(
+                label,
+                button.x + 12.0,
+                button.y + button.h - 10.0,
+                26.0,
+                SKYBLUE,
+            );
+        }
+    }
+
+    fn first_choice(&self) -> CompletionChoice {
+        if self.has_next {
+            CompletionChoice::Next
+        } else if self.in_library {
+            CompletionChoice::Library
+        } else {
+            CompletionChoice::Back
+        }
+    }
+}
+
+// 1:05 or 1:02:03
+pub fn format_time(secs: f64) -> String {
+    let secs = secs as u64;
+    let (hours, mins, secs) = (secs / 3600, secs / 60 % 60, secs % 60);
+    if hours > 0 {
+        format!("{}:{:02}:{:02}", hours, mins, secs)
+    } else {
+        format!("{}:{:02}", mins, secs)
+    }
+}
+
+fn panel_pos() -> (f32, f32) {
+    (
+        (screen_width() - WIDTH) / 2.0,
+        (screen_height() - HEIGHT) / 2.0,
+    )
+}
+
+fn buttons() -> (Rect, Rect) {
+    let (x, y) = panel_pos();
+    let y = y + HEIGHT - 55.0;
+    (
+        Rect::new(x + 20.0, y, 170.0, 38.0),
+        Rect::new(x + WIDTH - 140.0, y, 120.0, 38.0),
+    )
+}
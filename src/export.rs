@@ -0,0 +1,457 @@
+// Printable puzzles, laid out on pages and written as SVG (one document a page) or PDF.
+// Sizes are in points (1/72 inch), with the origin in the top left corner of the page.
+// A page has the title and author of the booklet on top and a grid of puzzles below them, each
+// with its caption, clues and grid. Blank puzzles have an empty grid, solutions a filled one.
+
+use std::fmt::Write;
+
+use crate::board::{Board, Cell, Clue, GUIDE_EVERY};
+
+pub const A4: (f32, f32) = (595.0, 842.0);
+pub const A5: (f32, f32) = (420.0, 595.0);
+pub const LETTER: (f32, f32) = (612.0, 792.0);
+
+const TITLE_SIZE: f32 = 20.0;
+const AUTHOR_SIZE: f32 = 12.0;
+const CAPTION_SIZE: f32 = 11.0;
+const SLOT_GAP: f32 = 18.0; // between the puzzles of a page
+const THIN_LINE: f32 = 0.5;
+const GUIDE_LINE: f32 = 1.5;
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum ExportMode {
+    Blank,
+    Solution,
+}
+
+#[derive(Clone, Debug)]
+pub struct PageOptions {
+    pub mode: ExportMode,
+    pub size: (f32, f32),
+    pub margin: f32,
+    pub title: Option<String>,
+    pub author: Option<String>,
+    pub columns: usize, // puzzles side by side on a page
+    pub rows: usize,
+}
+
+impl Default for PageOptions {
+    fn default() -> Self {
+        PageOptions {
+            mode: ExportMode::Blank,
+            size: A4,
+            margin: 36.0,
+            title: None,
+            author: None,
+            columns: 1,
+            rows: 1,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum Anchor {
+    Start,
+    Middle,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Shape {
+    Fill {
+        x: f32,
+        y: f32,
+        width: f32,
+        height: f32,
+    },
+    Line {
+        from: (f32, f32),
+        to: (f32, f32),
+        width: f32,
+    },
+    Text {
+        x: f32,
+        y: f32,
+        size: f32,
+        anchor: Anchor,
+        text: String,
+    },
+}
+
+// one SVG document for each page
+pub fn to_svg(puzzles: &[(&str, &Board)], options: &PageOptions) -> Vec<String> {
+    let (width, height) = options.size;
+    layout(puzzles, options)
+        .iter()
+        .map(|shapes| {
+            let mut svg = String::new();
+            writeln!(
+                svg,
+                concat!(
+                    r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}pt" height="{h}pt" "#,
+                    r#"viewBox="0 0 {w} {h}">"#
+                ),
+                w = width,
+                h = height
+            )
+            .unwrap();
+            writeln!(
+                svg,
+                r#"<rect width="{}" height="{}" fill="white"/>"#,
+                width, height
+            )
+            .unwrap();
+            for shape in shapes {
+                match shape {
+                    Shape::Fill {
+                        x,
+                        y,
+                        width,
+                        height,
+                    } => writeln!(
+                        svg,
+                        r#"<rect x="{:.2}" y="{:.2}" width="{:.2}" height="{:.2}" fill="black"/>"#,
+                        x, y, width, height
+                    ),
+                    Shape::Line { from, to, width } => writeln!(
+                        svg,
+                        concat!(
+                            r#"<line x1="{:.2}" y1="{:.2}" x2="{:.2}" y2="{:.2}" "#,
+                            r#"stroke="black" stroke-width="{}"/>"#
+                        ),
+                        from.0, from.1, to.0, to.1, width
+                    ),
+                    Shape::Text {
+                        x,
+                        y,
+                        size,
+                        anchor,
+                        text,
+                    } => writeln!(
+                        svg,
+                        concat!(
+                            r#"<text x="{:.2}" y="{:.2}" "#,
+                            r#"font-family="Helvetica, Arial, sans-serif" "#,
+                            r#"font-size="{:.2}" text-anchor="{}">{}</text>"#
+                        ),
+                        x,
+                        y,
+                        size,
+                        if *anchor == Anchor::Middle {
+                            "middle"
+                        } else {
+                            "start"
+                        },
+                        escape_xml(text)
+                    ),
+                }
+                .unwrap();
+            }
+            svg.push_str("</svg>\n");
+            svg
+        })
+        .collect()
+}
+
+// a PDF with a page for each page of the layout, text is set in the built-in Helvetica
+pub fn to_pdf(puzzles: &[(&str, &Board)], options: &PageOptions) -> Vec<u8> {
+    let (width, height) = options.size;
+    let pages = layout(puzzles, options);
+
+    // objects 1 and 2 are the catalog and the page tree, 3 the font, then a page and its
+    // contents for each page
+    let page_ids = (0..pages.len()).map(|n| 4 + 2 * n).collect::<Vec<_>>();
+    let mut objects = vec![
+        "<< /Type /Catalog /Pages 2 0 R >>".to_owned(),
+        format!(
+            "<< /Type /Pages /Kids [{}] /Count {} >>",
+            page_ids
+                .iter()
+                .map(|id| format!("{} 0 R", id))
+                .collect::<Vec<_>>()
+                .join(" "),
+            pages.len()
+        ),
+        "<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica /Encoding /WinAnsiEncoding >>"
+            .to_owned(),
+    ];
+    for (shapes, id) in pages.iter().zip(page_ids) {
+        let mut content = String::new();
+        for shape in shapes {
+            match shape {
+                Shape::Fill {
+                    x,
+                    y,
+                    width,
+                    height: h,
+                } => writeln!(
+                    content,
+                    "{:.2} {:.2} {:.2} {:.2} re f",
+                    x,
+                    height - y - h,
+                    width,
+                    h
+                ),
+                Shape::Line { from, to, width } => writeln!(
+                    content,
+                    "{} w {:.2} {:.2} m {:.2} {:.2} l S",
+                    width,
+                    from.0,
+                    height - from.1,
+                    to.0,
+                    height - to.1
+                ),
+                Shape::Text {
+                    x,
+                    y,
+                    size,
+                    anchor,
+                    text,
+                } => {
+                    let x = match anchor {
+                        Anchor::Start => *x,
+                        Anchor::Middle => x - text_width(text, *size) / 2.0,
+                    };
+                    writeln!(
+                        content,
+                        "BT /F1 {:.2} Tf {:.2} {:.2} Td ({}) Tj ET",
+                        size,
+                        x,
+                        height - y,
+                        escape_pdf(text)
+                    )
+                }
+            }
+            .unwrap();
+        }
+
+        objects.push(format!(
+            "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {} {}] \
+             /Resources << /Font << /F1 3 0 R >> >> /Contents {} 0 R >>",
+            width,
+            height,
+            id + 1
+        ));
+        objects.push(format!(
+            "<< /Length {} >>\nstream\n{}endstream",
+            win_ansi(&content).len(),
+            content
+        ));
+    }
+
+    let mut pdf = b"%PDF-1.4\n".to_vec();
+    let mut offsets = vec![];
+    for (n, object) in objects.iter().enumerate() {
+        offsets.push(pdf.len());
+        pdf.extend(format!("{} 0 obj\n", n + 1).bytes());
+        pdf.extend(win_ansi(object));
+        pdf.extend(b"\nendobj\n");
+    }
+    let xref = pdf.len();
+    let mut trailer = format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1);
+    for offset in offsets {
+        writeln!(trailer, "{:010} 00000 n ", offset).unwrap();
+    }
+    write!(
+        trailer,
+        "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n",
+        objects.len() + 1,
+        xref
+    )
+    .unwrap();
+    pdf.extend(trailer.bytes());
+    pdf
+}
+
+// the shapes of each page, the puzzles fill the pages in reading order
+fn layout(puzzles: &[(&str, &Board)], options: &PageOptions) -> Vec<Vec<Shape>> {
+    let (width, height) = options.size;
+    let margin = options.margin;
+    let (columns, rows) = (options.columns.max(1), options.rows.max(1));
+
+    let mut header = vec![];
+    let mut top = margin;
+    if let Some(title) = &options.title {
+        top += TITLE_SIZE;
+        header.push(text(margin, top, TITLE_SIZE, Anchor::Start, title));
+    }
+    if let Some(author) = &options.author {
+        top += AUTHOR_SIZE * 1.5;
+        header.push(text(margin, top, AUTHOR_SIZE, Anchor::Start, author));
+    }
+    if top > margin {
+        top += SLOT_GAP;
+    }
+
+    let slot_width = (width - 2.0 * margin - (columns - 1) as f32 * SLOT_GAP) / columns as f32;
+    let slot_height = (height - top - margin - (rows - 1) as f32 * SLOT_GAP) / rows as f32;
+
+    let per_page = columns * rows;
+    let page_count = puzzles.len().max(1).div_ceil(per_page);
+    (0..page_count)
+        .map(|n_page| {
+            let mut shapes = header.clone();
+            let on_page = puzzles.iter().skip(n_page * per_page).take(per_page);
+            for (i, (caption, board)) in on_page.enumerate() {
+                let x = margin + (i % columns) as f32 * (slot_width + SLOT_GAP);
+                let y = top + (i / columns) as f32 * (slot_height + SLOT_GAP);
+                let slot = (x, y, slot_width, slot_height);
+                shapes.extend(puzzle_shapes(caption, board, slot, options.mode));
+            }
+            shapes
+        })
+        .collect()
+}
+
+// a puzzle in the box (x, y, width, height), as big as fits
+fn puzzle_shapes(
+    caption: &str, board: &Board, (x, y, width, height): (f32, f32, f32, f32), mode: ExportMode,
+) -> Vec<Shape> {
+    let mut shapes = vec![];
+    let (mut y, mut height) = (y, height);
+    if !caption.is_empty() {
+        shapes.push(text(
+            x,
+            y + CAPTION_SIZE,
+            CAPTION_SIZE,
+            Anchor::Start,
+            caption,
+        ));
+        y += CAPTION_SIZE * 1.8;
+        height -= CAPTION_SIZE * 1.8;
+    }
+
+    let longest = |clues: &[Clue]| {
+        clues
+            .iter()
+            .map(|c| c.hints.len())
+            .max()
+            .unwrap_or(0)
+            .max(1)
+    };
+    let (row_hints, col_hints) = (longest(board.row_clues()), longest(board.col_clues()));
+    let cell = (width / (row_hints + board.cols) as f32)
+        .min(height / (col_hints + board.rows) as f32)
+        .max(0.0);
+    let grid_x = x + row_hints as f32 * cell;
+    let grid_y = y + col_hints as f32 * cell;
+
+    // the hints are aligned to the grid, the last one next to it
+    let hint_shape = |hint: usize, center_x: f32, center_y: f32| {
+        let digits = hint.to_string();
+        let size = (cell * 0.65).min(cell * 0.85 / text_width(&digits, 1.0));
+        text(
+            center_x,
+            center_y + size * 0.36,
+            size,
+            Anchor::Middle,
+            &digits,
+        )
+    };
+    for (n_row, clue) in board.row_clues().iter().enumerate() {
+        for (i, &hint) in clue.hints.iter().rev().enumerate() {
+            let center_x = grid_x - (i as f32 + 0.5) * cell;
+            let center_y = grid_y + (n_row as f32 + 0.5) * cell;
+            shapes.push(hint_shape(hint, center_x, center_y));
+        }
+    }
+    for (n_col, clue) in board.col_clues().iter().enumerate() {
+        for (i, &hint) in clue.hints.iter().rev().enumerate() {
+            let center_x = grid_x + (n_col as f32 + 0.5) * cell;
+            let center_y = grid_y - (i as f32 + 0.5) * cell;
+            shapes.push(hint_shape(hint, center_x, center_y));
+        }
+    }
+
+    if mode == ExportMode::Solution {
+        for (n_row, row) in board.data.iter().enumerate() {
+            for (n_col, &c) in row.iter().enumerate() {
+                if c == Cell::On {
+                    shapes.push(Shape::Fill {
+                        x: grid_x + n_col as f32 * cell,
+                        y: grid_y + n_row as f32 * cell,
+                        width: cell,
+                        height: cell,
+                    });
+                }
+            }
+        }
+    }
+
+    // thicker guides every few cells and around the grid
+    let line_width = |n: usize, count: usize| {
+        if n == 0 || n == count || n.is_multiple_of(GUIDE_EVERY) {
+            GUIDE_LINE
+        } else {
+            THIN_LINE
+        }
+    };
+    let (grid_width, grid_height) = (board.cols as f32 * cell, board.rows as f32 * cell);
+    for n_row in 0..=board.rows {
+        let line_y = grid_y + n_row as f32 * cell;
+        shapes.push(Shape::Line {
+            from: (grid_x, line_y),
+            to: (grid_x + grid_width, line_y),
+            width: line_width(n_row, board.rows),
+        });
+    }
+    for n_col in 0..=board.cols {
+        let line_x = grid_x + n_col as f32 * cell;
+        shapes.push(Shape::Line {
+            from: (line_x, grid_y),
+            to: (line_x, grid_y + grid_height),
+            width: line_width(n_col, board.cols),
+        });
+    }
+
+    shapes
+}
+
+fn text(x: f32, y: f32, size: f32, anchor: Anchor, text: &str) -> Shape {
+    Shape::Text {
+        x,
+        y,
+        size,
+        anchor,
+        text: text.to_owned(),
+    }
+}
+
+// digits are 0.556 em wide in Helvetica, other chars about half an em
+fn text_width(text: &str, size: f32) -> f32 {
+    text.chars()
+        .map(|ch| if ch.is_ascii_digit() { 0.556 } else { 0.5 })
+        .sum::<f32>()
+        * size
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn escape_pdf(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace('(', "\\(")
+        .replace(')', "\\)")
+}
+
+// the chars of WinAnsiEncoding at 0x80 to 0x9f, the rest of it is Latin-1
+const WIN_ANSI_HIGH: [char; 32] = [
+    '€', '\0', '‚', 'ƒ', '„', '…', '†', '‡', 'ˆ', '‰', 'Š', '‹', 'Œ', '\0', 'Ž', '\0',
+    '\0', '‘', '’', '“', '”', '•', '–', '—', '˜', '™', 'š', '›', 'œ', '\0', 'ž', 'Ÿ',
+];
+
+// chars without a WinAnsiEncoding code are printed as '?'
+fn win_ansi(text: &str) -> Vec<u8> {
+    text.chars()
+        .map(|ch| match ch as u32 {
+            0..=0x7f | 0xa0..=0xff => ch as u8,
+            _ => WIN_ANSI_HIGH
+                .iter()
+                .position(|&high| high == ch && ch != '\0')
+                .map_or(b'?', |i| 0x80 + i as u8),
+        })
+        .collect()
+}
@@ -0,0 +1,259 @@
+// C API of the solver, built into the cdylib with `--features ffi`.
+// The header lives in include/nonogram_solver.h, regenerate it after changing this file:
+//   cbindgen --config cbindgen.toml --output include/nonogram_solver.h
+
+use std::mem;
+use std::panic::{self, AssertUnwindSafe};
+use std::ptr;
+use std::slice;
+
+use crate::analysis::{self, Uniqueness};
+use crate::board::{Board, Cell, Clue};
+use crate::solver::{self, SolveStatus};
+
+/// Puzzle handle, created by `nonogram_puzzle_new` and released by `nonogram_puzzle_free`.
+pub struct NonogramPuzzle {
+    board: Board,
+    status: NonogramStatus,
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum NonogramCell {
+    Unknown = 0,
+    Filled = 1,
+    Empty = 2,
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum NonogramStatus {
+    /// Every cell is known.
+    Solved = 0,
+    /// Not solved yet, or the line rules got stuck and guessing wasn't allowed.
+    Unsolved = 1,
+    /// Guessing found that the clues (and the known cells) have no solution.
+    NoSolution = 2,
+    /// Guessing found more than one solution, the board is left as the line rules solved it.
+    MultipleSolutions = 3,
+    /// A null pointer or out of range argument was passed.
+    InvalidArgument = 4,
+    /// The solver failed unexpectedly, the puzzle is left unchanged.
+    InternalError = 5,
+    /// The clues are inconsistent (e.g. hints longer than the line), the puzzle is left unchanged.
+    InvalidClues = 6,
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Default)]
+pub struct NonogramSolveOptions {
+    /// Forget the known cells before solving.
+    pub clear_board: bool,
+    /// Finish boards the line rules can't solve by guessing.
+    pub allow_guessing: bool,
+}
+
+impl From<Cell> for NonogramCell {
+    fn from(cell: Cell) -> Self {
+        match cell {
+            Cell::None => NonogramCell::Unknown,
+            Cell::On => NonogramCell::Filled,
+            Cell::Off => NonogramCell::Empty,
+        }
+    }
+}
+
+impl From<NonogramCell> for Cell {
+    fn from(cell: NonogramCell) -> Self {
+        match cell {
+            NonogramCell::Unknown => Cell::None,
+            NonogramCell::Filled => Cell::On,
+            NonogramCell::Empty => Cell::Off,
+        }
+    }
+}
+
+// None if a C array of usize this long can't be a slice
+fn array_len(len: usize) -> Option<usize> {
+    Some(len).filter(|&len| len <= isize::MAX as usize / mem::size_of::<usize>())
+}
+
+unsafe fn read_clues(
+    lines: usize, hints: *const usize, hint_counts: *const usize,
+) -> Option<Vec<Clue>> {
+    if lines == 0 {
+        return Some(vec![]);
+    }
+    if hint_counts.is_null() {
+        return None;
+    }
+
+    let hint_counts = slice::from_raw_parts(hint_counts, array_len(lines)?);
+    let total_hints = hint_counts
+        .iter()
+        .try_fold(0usize, |total, &count| total.checked_add(count))
+        .and_then(array_len)?;
+    let hints = if total_hints == 0 {
+        &[][..]
+    } else if hints.is_null() {
+        return None;
+    } else {
+        slice::from_raw_parts(hints, total_hints)
+    };
+
+    let mut clues = vec![];
+    let mut start = 0;
+    for &count in hint_counts {
+        clues.push(Clue::new(hints[start..start + count].to_vec()));
+        start += count;
+    }
+    Some(clues)
+}
+
+/// Creates a blank puzzle. The hints of all rows are passed one after another in `row_hints`,
+/// `row_hint_counts[i]` is the number of hints of row `i`, and the same for columns.
+/// Returns null if a required pointer is null or the puzzle is too big.
+///
+/// # Safety
+/// `row_hint_counts` must point to `rows` values and `row_hints` to as many values as their sum
+/// (likewise for the column arguments).
+#[no_mangle]
+pub unsafe extern "C" fn nonogram_puzzle_new(
+    rows: usize, cols: usize, row_hints: *const usize, row_hint_counts: *const usize,
+    col_hints: *const usize, col_hint_counts: *const usize,
+) -> *mut NonogramPuzzle {
+    let puzzle = panic::catch_unwind(|| {
+        rows.checked_mul(cols)?;
+        let row_clues = read_clues(rows, row_hints, row_hint_counts)?;
+        let col_clues = read_clues(cols, col_hints, col_hint_counts)?;
+        Some(Box::new(NonogramPuzzle {
+            board: Board::with_clues(row_clues, col_clues),
+            status: NonogramStatus::Unsolved,
+        }))
+    });
+    match puzzle {
+        Ok(Some(puzzle)) => Box::into_raw(puzzle),
+        _ => ptr::null_mut(),
+    }
+}
+
+/// # Safety
+/// `puzzle` must come from `nonogram_puzzle_new` and must not be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn nonogram_puzzle_free(puzzle: *mut NonogramPuzzle) {
+    if !puzzle.is_null() {
+        drop(Box::from_raw(puzzle));
+    }
+}
+
+/// # Safety
+/// `puzzle` must be null or come from `nonogram_puzzle_new`.
+#[no_mangle]
+pub unsafe extern "C" fn nonogram_puzzle_rows(puzzle: *const NonogramPuzzle) -> usize {
+    puzzle.as_ref().map_or(0, |puzzle| puzzle.board.rows)
+}
+
+/// # Safety
+/// `puzzle` must be null or come from `nonogram_puzzle_new`.
+#[no_mangle]
+pub unsafe extern "C" fn nonogram_puzzle_cols(puzzle: *const NonogramPuzzle) -> usize {
+    puzzle.as_ref().map_or(0, |puzzle| puzzle.board.cols)
+}
+
+/// Marks a cell as known before solving. `cell` is a `NonogramCell`. Returns false if the cell
+/// is out of range or `cell` isn't one of the `NonogramCell` values.
+///
+/// # Safety
+/// `puzzle` must be null or come from `nonogram_puzzle_new`.
+#[no_mangle]
+pub unsafe extern "C" fn nonogram_puzzle_set_cell(
+    puzzle: *mut NonogramPuzzle, row: usize, col: usize, cell: u32,
+) -> bool {
+    // any other value would be undefined behaviour as a NonogramCell
+    let cells = [NonogramCell::Unknown, NonogramCell::Filled, NonogramCell::Empty];
+    let cell = match cells.iter().find(|&&c| c as u32 == cell) {
+        Some(&cell) => Cell::from(cell),
+        None => return false,
+    };
+    let target = puzzle
+        .as_mut()
+        .and_then(|puzzle| puzzle.board.data.get_mut(row))
+        .and_then(|cells| cells.get_mut(col));
+    match target {
+        Some(target) => {
+            *target = cell;
+            true
+        }
+        None => false,
+    }
+}
+
+/// Writes the cell to `cell`. Returns false if the cell is out of range.
+///
+/// # Safety
+/// `puzzle` must be null or come from `nonogram_puzzle_new`, `cell` must be null or writable.
+#[no_mangle]
+pub unsafe extern "C" fn nonogram_puzzle_get_cell(
+    puzzle: *const NonogramPuzzle, row: usize, col: usize, cell: *mut NonogramCell,
+) -> bool {
+    let source = puzzle
+        .as_ref()
+        .and_then(|puzzle| puzzle.board.data.get(row))
+        .and_then(|cells| cells.get(col));
+    match (source, cell.as_mut()) {
+        (Some(source), Some(cell)) => {
+            *cell = (*source).into();
+            true
+        }
+        _ => false,
+    }
+}
+
+/// Solves the puzzle in place. `options` may be null to use the defaults (all false).
+///
+/// # Safety
+/// `puzzle` must be null or come from `nonogram_puzzle_new`, `options` must be null or valid.
+#[no_mangle]
+pub unsafe extern "C" fn nonogram_puzzle_solve(
+    puzzle: *mut NonogramPuzzle, options: *const NonogramSolveOptions,
+) -> NonogramStatus {
+    let puzzle = match puzzle.as_mut() {
+        Some(puzzle) => puzzle,
+        None => return NonogramStatus::InvalidArgument,
+    };
+    let options = options.as_ref().copied().unwrap_or_default();
+
+    let mut board = puzzle.board.clone();
+    let solved = panic::catch_unwind(AssertUnwindSafe(|| {
+        match solver::solve(&mut board, options.clear_board).status {
+            SolveStatus::Solved => NonogramStatus::Solved,
+            SolveStatus::InvalidClues => NonogramStatus::InvalidClues,
+            SolveStatus::Unsolved if !options.allow_guessing => NonogramStatus::Unsolved,
+            SolveStatus::Unsolved => match analysis::complete_by_search(&mut board) {
+                Uniqueness::Unique => NonogramStatus::Solved,
+                Uniqueness::Multiple => NonogramStatus::MultipleSolutions,
+                Uniqueness::NoSolution => NonogramStatus::NoSolution,
+            },
+        }
+    }));
+
+    puzzle.status = match solved {
+        Ok(status) => {
+            puzzle.board = board;
+            status
+        }
+        Err(_) => NonogramStatus::InternalError,
+    };
+    puzzle.status
+}
+
+/// Status of the last `nonogram_puzzle_solve` call, `Unsolved` before the first one.
+///
+/// # Safety
+/// `puzzle` must be null or come from `nonogram_puzzle_new`.
+#[no_mangle]
+pub unsafe extern "C" fn nonogram_puzzle_status(puzzle: *const NonogramPuzzle) -> NonogramStatus {
+    puzzle
+        .as_ref()
+        .map_or(NonogramStatus::InvalidArgument, |puzzle| puzzle.status)
+}
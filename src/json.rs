@@ -0,0 +1,215 @@
+// JSON format used to exchange puzzles and solver results.
+//
+// Board:
+//   {
+//     "rows": 2,
+//     "cols": 3,
+//     "row_clues": [[1, 1], [3]],   // hints of every row, top to bottom
+//     "col_clues": [[2], [1], [2]], // hints of every col, left to right
+//     "grid": ["X.X", "XXX"]        // one string per row, cells as in `Cell::as_char`
+//   }
+// "grid" may be omitted, the board is blank then. Clues are checked with `Board::validate`.
+//
+// Cell: one character string, "X" (On), "." (Off) or " " (None).
+//
+// Rule: { "n": 0, "is_col": false, "hints": [1, 1] }
+//
+// Solve report:
+//   {
+//     "status": "solved",           // or "unsolved", "invalid_clues"
+//     "stats": { "iterations": 3, "lines_processed": 15, "cells_solved": 6 },
+//     "board": { ... }              // board after solving
+//   }
+
+use std::convert::TryFrom;
+use std::fmt;
+
+use itertools::Itertools;
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::board::{Board, Cell, Clue, ClueError};
+use crate::solver::{SolveOutcome, SolveStats, SolveStatus};
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BoardJson {
+    pub rows: usize,
+    pub cols: usize,
+    pub row_clues: Vec<Vec<usize>>,
+    pub col_clues: Vec<Vec<usize>>,
+    #[serde(default)]
+    pub grid: Vec<String>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum JsonError {
+    ClueCount {
+        is_col: bool,
+        expected: usize,
+        found: usize,
+    },
+    GridRowCount {
+        expected: usize,
+        found: usize,
+    },
+    GridRowLength {
+        row: usize,
+        expected: usize,
+        found: usize,
+    },
+    UnknownCell {
+        row: usize,
+        ch: char,
+    },
+    InvalidClues(Vec<ClueError>),
+}
+
+impl fmt::Display for JsonError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            JsonError::ClueCount {
+                is_col,
+                expected,
+                found,
+            } => {
+                let lines = if *is_col { "col_clues" } else { "row_clues" };
+                write!(f, "{} has {} entries, expected {}", lines, found, expected)
+            }
+            JsonError::GridRowCount { expected, found } => {
+                write!(f, "grid has {} rows, expected {}", found, expected)
+            }
+            JsonError::GridRowLength {
+                row,
+                expected,
+                found,
+            } => {
+                write!(
+                    f,
+                    "grid row {} has {} cells, expected {}",
+                    row, found, expected
+                )
+            }
+            JsonError::UnknownCell { row, ch } => {
+                write!(f, "grid row {} contains unknown cell {:?}", row, ch)
+            }
+            JsonError::InvalidClues(errors) => {
+                write!(f, "invalid clues: {}", errors.iter().join("; "))
+            }
+        }
+    }
+}
+
+impl From<Board> for BoardJson {
+    fn from(board: Board) -> Self {
+        let hints = |clues: &[Clue]| clues.iter().map(|c| c.hints.clone()).collect();
+        BoardJson {
+            rows: board.rows,
+            cols: board.cols,
+            row_clues: hints(board.row_clues()),
+            col_clues: hints(board.col_clues()),
+            grid: format_grid(&board),
+        }
+    }
+}
+
+impl TryFrom<BoardJson> for Board {
+    type Error = JsonError;
+
+    fn try_from(json: BoardJson) -> Result<Self, Self::Error> {
+        for &(is_col, expected, found) in &[
+            (false, json.rows, json.row_clues.len()),
+            (true, json.cols, json.col_clues.len()),
+        ] {
+            if expected != found {
+                return Err(JsonError::ClueCount {
+                    is_col,
+                    expected,
+                    found,
+                });
+            }
+        }
+
+        let clues = |hints: Vec<Vec<usize>>| hints.into_iter().map(Clue::new).collect();
+        let mut board = Board::with_clues(clues(json.row_clues), clues(json.col_clues));
+        let errors = board.validate();
+        if !errors.is_empty() {
+            return Err(JsonError::InvalidClues(errors));
+        }
+
+        if !json.grid.is_empty() {
+            board.data = parse_grid(&json.grid, json.rows, json.cols)?;
+        }
+
+        Ok(board)
+    }
+}
+
+pub fn parse_grid(grid: &[String], rows: usize, cols: usize) -> Result<Vec<Vec<Cell>>, JsonError> {
+    if grid.len() != rows {
+        return Err(JsonError::GridRowCount {
+            expected: rows,
+            found: grid.len(),
+        });
+    }
+
+    let mut data = vec![];
+    for (n_row, row) in grid.iter().enumerate() {
+        let cells = row
+            .chars()
+            .map(|ch| match ch {
+                'X' | '.' | ' ' => Ok(Cell::from_char(ch)),
+                _ => Err(JsonError::UnknownCell { row: n_row, ch }),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        if cells.len() != cols {
+            return Err(JsonError::GridRowLength {
+                row: n_row,
+                expected: cols,
+                found: cells.len(),
+            });
+        }
+        data.push(cells);
+    }
+
+    Ok(data)
+}
+
+pub fn format_grid(board: &Board) -> Vec<String> {
+    board
+        .data
+        .iter()
+        .map(|row| row.iter().map(Cell::as_char).collect())
+        .collect()
+}
+
+impl Serialize for Cell {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_char(self.as_char())
+    }
+}
+
+impl<'de> Deserialize<'de> for Cell {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        match char::deserialize(deserializer)? {
+            ch @ 'X' | ch @ '.' | ch @ ' ' => Ok(Cell::from_char(ch)),
+            ch => Err(D::Error::custom(format!("unknown cell {:?}", ch))),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SolveReport {
+    pub status: SolveStatus,
+    pub stats: SolveStats,
+    pub board: Board,
+}
+
+impl SolveReport {
+    pub fn new(outcome: SolveOutcome, board: Board) -> Self {
+        SolveReport {
+            status: outcome.status,
+            stats: outcome.stats,
+            board,
+        }
+    }
+}
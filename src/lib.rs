@@ -0,0 +1,19 @@
+pub mod analysis;
+pub mod board;
+pub mod export;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+pub mod json;
+pub mod library;
+pub mod line;
+pub mod progress;
+pub mod puzzles;
+pub mod render;
+#[cfg(feature = "python")]
+mod python;
+#[cfg(feature = "server")]
+pub mod server;
+pub mod solver;
+mod tests;
+#[cfg(target_arch = "wasm32")]
+pub mod wasm;
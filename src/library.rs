@@ -0,0 +1,393 @@
+// Puzzles kept as files in one directory, listed by the start screen. A puzzle file is board JSON
+// (see `json`) with optional metadata next to the board fields:
+//   {
+//     "title": "House",
+//     "author": "shoco",
+//     "date": "2021-03-14",
+//     "uniqueness": "unique",   // as in `analysis`, graded when the puzzle was saved
+//     "difficulty": "easy",
+//     "rows": 2,
+//     ...
+//   }
+// Puzzles saved from Create mode keep the drawn picture in "grid".
+//
+// The names of the completed ones are stored next to them in `completed.json`, the best time of
+// each in `best_times.json` (seconds by name) and the progress on unfinished ones in
+// `progress/<name>.json` (see `progress`).
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use itertools::Itertools;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::analysis::{self, Difficulty, Uniqueness};
+use crate::board::{Board, Cell};
+use crate::progress::Progress;
+
+pub const COMPLETED_FILE: &str = "completed.json";
+pub const BEST_TIMES_FILE: &str = "best_times.json";
+pub const PROGRESS_DIR: &str = "progress";
+pub const GRADE_NODES: usize = 20_000; // search budget of grading a puzzle as it's saved
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct PuzzleInfo {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub date: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub uniqueness: Option<Uniqueness>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub difficulty: Option<Difficulty>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PuzzleFile {
+    #[serde(flatten)]
+    pub info: PuzzleInfo,
+    #[serde(flatten)]
+    pub board: Board,
+}
+
+impl PuzzleFile {
+    // a drawn puzzle with its clues generated from the picture, graded and dated now. A puzzle
+    // that takes more than GRADE_NODES to grade is left ungraded, the library grades it later.
+    pub fn new(picture: &Board, title: &str, author: &str) -> Self {
+        let mut board = picture.clone();
+        board.generate_clues_from_board();
+        let non_empty = |s: &str| Some(s.trim().to_owned()).filter(|s| !s.is_empty());
+        let graded = analysis::check_uniqueness_within(&board, GRADE_NODES);
+        let (uniqueness, difficulty) = match graded {
+            Ok(Uniqueness::Unique) => {
+                (Some(Uniqueness::Unique), Some(analysis::grade_unique(&board)))
+            }
+            Ok(uniqueness) => (Some(uniqueness), None),
+            Err(_) => (None, None),
+        };
+
+        PuzzleFile {
+            info: PuzzleInfo {
+                title: non_empty(title),
+                author: non_empty(author),
+                date: Some(date_of(SystemTime::now())),
+                uniqueness,
+                difficulty,
+            },
+            board,
+        }
+    }
+
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let json = fs::read_to_string(path).map_err(|err| err.to_string())?;
+        serde_json::from_str(&json).map_err(|err| err.to_string())
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, serde_json::to_string_pretty(self)?)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct PuzzleEntry {
+    pub name: String,
+    pub path: PathBuf,
+    pub info: PuzzleInfo,
+    pub board: Board,           // blank, even if the file has a grid
+    pub picture: Option<Board>, // the grid of the file, if any cell of it is set
+    pub difficulty: Option<Difficulty>,
+    pub solution: Option<Board>,
+    pub graded: bool, // difficulty and solution are known, see `grade`
+}
+
+impl PuzzleEntry {
+    fn new(name: String, path: PathBuf, file: PuzzleFile) -> Self {
+        let PuzzleFile { info, board } = file;
+        let picture =
+            Some(board.clone()).filter(|b| b.data.iter().flatten().any(|c| *c != Cell::None));
+        let mut board = board;
+        board.clear_board();
+
+        // puzzles saved from Create mode are graded already, and their picture is the solution
+        let grade = match (info.uniqueness, &picture) {
+            (Some(Uniqueness::Unique), Some(picture)) if picture.satisfies_clues() => {
+                let mut solution = picture.clone();
+                for cell in solution.data.iter_mut().flatten() {
+                    if *cell == Cell::None {
+                        *cell = Cell::Off;
+                    }
+                }
+                Some((info.difficulty, Some(solution)))
+            }
+            (Some(Uniqueness::Unique), _) | (None, _) => None,
+            (Some(_), _) => Some((None, None)),
+        };
+        let graded = grade.is_some();
+        let (difficulty, solution) = grade.unwrap_or((None, None));
+
+        PuzzleEntry {
+            name,
+            path,
+            info,
+            picture,
+            difficulty,
+            solution,
+            graded,
+            board,
+        }
+    }
+
+    // searches for the solution, which can take long for big puzzles
+    pub fn grade(&mut self) {
+        self.difficulty = analysis::grade_difficulty(&self.board);
+        self.solution = analysis::find_unique_solution(&self.board);
+        self.graded = true;
+    }
+
+    pub fn title(&self) -> &str {
+        self.info.title.as_deref().unwrap_or(&self.name)
+    }
+}
+
+#[derive(Debug)]
+pub struct Library {
+    pub dir: PathBuf,
+    pub puzzles: Vec<PuzzleEntry>,
+    pub errors: Vec<(PathBuf, String)>, // files that couldn't be loaded
+    completed: BTreeSet<String>,
+    best_times: BTreeMap<String, f64>,
+    in_progress: BTreeSet<String>, // names with a progress file
+    unreadable: Vec<&'static str>, // side files that couldn't be loaded, never written over
+}
+
+impl Library {
+    pub fn new(dir: impl AsRef<Path>) -> Self {
+        Library {
+            dir: dir.as_ref().to_path_buf(),
+            puzzles: vec![],
+            errors: vec![],
+            completed: BTreeSet::new(),
+            best_times: BTreeMap::new(),
+            in_progress: BTreeSet::new(),
+            unreadable: vec![],
+        }
+    }
+
+    // a missing directory is an empty library
+    pub fn load(dir: impl AsRef<Path>) -> io::Result<Self> {
+        let dir = dir.as_ref().to_path_buf();
+        let mut library = Library::new(&dir);
+        if !dir.exists() {
+            return Ok(library);
+        }
+
+        let mut paths = fs::read_dir(&dir)?
+            .map(|entry| entry.map(|e| e.path()))
+            .collect::<io::Result<Vec<_>>>()?;
+        paths.sort();
+
+        for path in paths {
+            let file_name = path.file_name().and_then(|f| f.to_str()).unwrap_or("");
+            let is_puzzle = path.extension().and_then(|e| e.to_str()) == Some("json")
+                && ![COMPLETED_FILE, BEST_TIMES_FILE].contains(&file_name);
+            let name = match path.file_stem().and_then(|s| s.to_str()) {
+                Some(name) if is_puzzle => name.to_owned(),
+                _ => continue,
+            };
+
+            match PuzzleFile::load(&path) {
+                Ok(file) => library.puzzles.push(PuzzleEntry::new(name, path, file)),
+                Err(err) => library.errors.push((path, err)),
+            }
+        }
+
+        match library.read_json(COMPLETED_FILE) {
+            Ok(completed) => library.completed = completed.unwrap_or_default(),
+            Err(err) => {
+                library.errors.push((dir.join(COMPLETED_FILE), err.to_string()));
+                library.unreadable.push(COMPLETED_FILE);
+            }
+        }
+        match library.read_json(BEST_TIMES_FILE) {
+            Ok(best_times) => library.best_times = best_times.unwrap_or_default(),
+            Err(err) => {
+                library.errors.push((dir.join(BEST_TIMES_FILE), err.to_string()));
+                library.unreadable.push(BEST_TIMES_FILE);
+            }
+        }
+
+        let progress_dir = dir.join(PROGRESS_DIR);
+        if progress_dir.exists() {
+            for entry in fs::read_dir(&progress_dir)? {
+                let path = entry?.path();
+                if path.extension().and_then(|e| e.to_str()) == Some("json") {
+                    if let Some(name) = path.file_stem().and_then(|s| s.to_str()) {
+                        library.in_progress.insert(name.to_owned());
+                    }
+                }
+            }
+        }
+
+        Ok(library)
+    }
+
+    pub fn get(&self, name: &str) -> Option<&PuzzleEntry> {
+        self.puzzles.iter().find(|entry| entry.name == name)
+    }
+
+    pub fn grade_all(&mut self) {
+        for entry in self.puzzles.iter_mut().filter(|entry| !entry.graded) {
+            entry.grade();
+        }
+    }
+
+    // takes the grade of an entry graded elsewhere, unless the puzzle was replaced meanwhile
+    pub fn set_graded(&mut self, graded: PuzzleEntry) {
+        let entry = self.puzzles.iter_mut().find(|entry| entry.name == graded.name);
+        if let Some(entry) = entry {
+            if !entry.graded
+                && entry.board.row_clues() == graded.board.row_clues()
+                && entry.board.col_clues() == graded.board.col_clues()
+            {
+                entry.difficulty = graded.difficulty;
+                entry.solution = graded.solution;
+                entry.graded = true;
+            }
+        }
+    }
+
+    // writes the puzzle as `<name>.json`, replacing the puzzle of that name and the progress on
+    // it if there is one
+    pub fn save_puzzle(&mut self, name: &str, file: PuzzleFile) -> io::Result<()> {
+        let path = self.dir.join(format!("{}.json", name));
+        file.save(&path)?;
+        self.clear_progress(name)?;
+
+        let entry = PuzzleEntry::new(name.to_owned(), path, file);
+        self.puzzles.retain(|e| e.name != name);
+        let pos = self.puzzles.partition_point(|e| e.name.as_str() < name);
+        self.puzzles.insert(pos, entry);
+        Ok(())
+    }
+
+    // a name for a new puzzle with this title that isn't taken yet
+    pub fn name_for(&self, title: &str) -> String {
+        let slug = title
+            .to_lowercase()
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|word| !word.is_empty())
+            .join("-");
+        let slug = if slug.is_empty() { "puzzle" } else { &slug };
+
+        (1..)
+            .map(|n| match n {
+                1 => slug.to_owned(),
+                _ => format!("{}-{}", slug, n),
+            })
+            .find(|name| {
+                self.get(name).is_none() && !self.dir.join(format!("{}.json", name)).exists()
+            })
+            .unwrap()
+    }
+
+    pub fn is_completed(&self, name: &str) -> bool {
+        self.completed.contains(name)
+    }
+
+    pub fn mark_completed(&mut self, name: &str) -> io::Result<()> {
+        if !self.completed.insert(name.to_owned()) {
+            return Ok(());
+        }
+        self.write_json(COMPLETED_FILE, &self.completed)
+    }
+
+    pub fn best_time(&self, name: &str) -> Option<f64> {
+        self.best_times.get(name).copied()
+    }
+
+    // keeps the time if it beats the best one so far, and tells whether it did
+    pub fn record_time(&mut self, name: &str, secs: f64) -> io::Result<bool> {
+        if self.best_time(name).is_some_and(|best| best <= secs) {
+            return Ok(false);
+        }
+        self.best_times.insert(name.to_owned(), secs);
+        self.write_json(BEST_TIMES_FILE, &self.best_times)?;
+        Ok(true)
+    }
+
+    // the first puzzle after this one that isn't completed yet, or just the one after it if all
+    // of them are
+    pub fn next_puzzle(&self, name: &str) -> Option<&PuzzleEntry> {
+        let pos = self.puzzles.iter().position(|e| e.name == name)?;
+        let mut after = self.puzzles.iter().cycle().skip(pos + 1).take(self.puzzles.len() - 1);
+        let next = after.clone().next();
+        after.find(|e| !self.is_completed(&e.name)).or(next)
+    }
+
+    pub fn progress_path(&self, name: &str) -> PathBuf {
+        self.dir.join(PROGRESS_DIR).join(format!("{}.json", name))
+    }
+
+    pub fn has_progress(&self, name: &str) -> bool {
+        self.in_progress.contains(name)
+    }
+
+    pub fn save_progress(&mut self, name: &str, progress: &Progress) -> io::Result<()> {
+        progress.save(&self.progress_path(name))?;
+        self.in_progress.insert(name.to_owned());
+        Ok(())
+    }
+
+    pub fn clear_progress(&mut self, name: &str) -> io::Result<()> {
+        Progress::clear(&self.progress_path(name))?;
+        self.in_progress.remove(name);
+        Ok(())
+    }
+
+    // None if the file isn't there
+    fn read_json<T: DeserializeOwned>(&self, file: &str) -> io::Result<Option<T>> {
+        let path = self.dir.join(file);
+        if !path.exists() {
+            return Ok(None);
+        }
+        let json = fs::read_to_string(&path)?;
+        serde_json::from_str(&json)
+            .map(Some)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+    }
+
+    fn write_json<T: Serialize>(&self, file: &str, value: &T) -> io::Result<()> {
+        if self.unreadable.contains(&file) {
+            let message = format!("{} couldn't be loaded, not writing over it", file);
+            return Err(io::Error::other(message));
+        }
+        fs::create_dir_all(&self.dir)?;
+        fs::write(self.dir.join(file), serde_json::to_string_pretty(value)?)
+    }
+}
+
+// YYYY-MM-DD in UTC, from the days since 1970-01-01 as in
+// http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+pub(crate) fn date_of(time: SystemTime) -> String {
+    let secs = time.duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs());
+    let z = (secs / 86400) as i64 + 719468;
+    let era = z.div_euclid(146097);
+    let day_of_era = z.rem_euclid(146097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
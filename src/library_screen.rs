@@ -0,0 +1,209 @@
+use std::sync::mpsc::{channel, Receiver};
+use std::thread;
+
+use macroquad::prelude::*;
+
+use nonogram_solver::board::{Board, Cell};
+use nonogram_solver::library::{Library, PuzzleEntry};
+
+use crate::completion_screen::format_time;
+
+const TOP: f32 = 110.0;
+const ROW_HEIGHT: f32 = 72.0;
+const THUMBNAIL_SIZE: f32 = 60.0;
+const BLANK_SIZES: [usize; 4] = [5, 10, 15, 20];
+
+pub enum Choice {
+    Open(String),
+    Edit(String),
+    NewBlank(usize),
+}
+
+// The start screen: every puzzle of the library with a thumbnail, and buttons for new blank
+// puzzles to draw in Create mode. Thumbnails only show the picture once the puzzle is completed.
+// Left click plays a puzzle, right click opens its picture in Create mode. Puzzles that weren't
+// graded when they were saved are graded on a background thread.
+pub struct LibraryScreen {
+    pub library: Library,
+    scroll: f32,
+    grades: Receiver<PuzzleEntry>,
+}
+
+impl LibraryScreen {
+    pub fn new(library: Library) -> Self {
+        let (grade_tx, grade_rx) = channel();
+        let ungraded = library
+            .puzzles
+            .iter()
+            .filter(|entry| !entry.graded)
+            .cloned()
+            .collect::<Vec<_>>();
+        thread::spawn(move || {
+            for mut entry in ungraded {
+                entry.grade();
+                if grade_tx.send(entry).is_err() {
+                    break;
+                }
+            }
+        });
+
+        LibraryScreen {
+            library,
+            scroll: 0.0,
+            grades: grade_rx,
+        }
+    }
+
+    // called every frame, also while a puzzle is played
+    pub fn receive_grades(&mut self) {
+        while let Ok(graded) = self.grades.try_recv() {
+            self.library.set_graded(graded);
+        }
+    }
+
+    pub fn update(&mut self) -> Option<Choice> {
+        let wheel = mouse_wheel().1;
+        if wheel != 0.0 {
+            let list_height = self.library.puzzles.len() as f32 * ROW_HEIGHT;
+            let max_scroll = (list_height - (screen_height() - TOP)).max(0.0);
+            self.scroll = (self.scroll - wheel.signum() * ROW_HEIGHT)
+                .max(0.0)
+                .min(max_scroll);
+        }
+
+        let (left, right) = (
+            is_mouse_button_pressed(MouseButton::Left),
+            is_mouse_button_pressed(MouseButton::Right),
+        );
+        if !left && !right {
+            return None;
+        }
+        let mouse = Vec2::from(mouse_position());
+
+        for (i, &size) in BLANK_SIZES.iter().enumerate() {
+            if left && blank_button(i).contains(mouse) {
+                return Some(Choice::NewBlank(size));
+            }
+        }
+        if mouse.y < TOP {
+            return None;
+        }
+        for (i, entry) in self.library.puzzles.iter().enumerate() {
+            if !self.entry_rect(i).contains(mouse) {
+                continue;
+            }
+            if left {
+                return Some(Choice::Open(entry.name.clone()));
+            } else if entry.picture.is_some() {
+                return Some(Choice::Edit(entry.name.clone()));
+            }
+        }
+        None
+    }
+
+    pub fn draw(&self) {
+        for (i, entry) in self.library.puzzles.iter().enumerate() {
+            let rect = self.entry_rect(i);
+            if rect.y + rect.h < TOP || rect.y > screen_height() {
+                continue;
+            }
+            self.draw_entry(entry, rect);
+        }
+
+        // the header covers entries scrolled past the top
+        draw_rectangle(0.0, 0.0, screen_width(), TOP, BLACK);
+        draw_text("Puzzles", 10.0, 40.0, 40.0, WHITE);
+        if !self.library.errors.is_empty() {
+            let errors = format!("{} files couldn't be loaded", self.library.errors.len());
+            draw_text(&errors, 200.0, 40.0, 20.0, RED);
+        }
+
+        draw_text("New blank puzzle:", 10.0, 82.0, 24.0, WHITE);
+        for (i, size) in BLANK_SIZES.iter().enumerate() {
+            let Rect { x, y, w, h } = blank_button(i);
+            draw_rectangle_lines(x, y, w, h, 1.0, SKYBLUE);
+            draw_text(
+                &format!("{}x{}", size, size),
+                x + 8.0,
+                y + h - 8.0,
+                24.0,
+                SKYBLUE,
+            );
+        }
+        draw_line(0.0, TOP - 1.0, screen_width(), TOP - 1.0, 2.0, SKYBLUE);
+    }
+
+    fn draw_entry(&self, entry: &PuzzleEntry, rect: Rect) {
+        let completed = self.library.is_completed(&entry.name);
+        let thumbnail = match &entry.solution {
+            Some(solution) if completed => solution,
+            _ => &entry.board,
+        };
+        draw_thumbnail(thumbnail, rect.x + 6.0, rect.y + 6.0, THUMBNAIL_SIZE);
+
+        let text_x = rect.x + THUMBNAIL_SIZE + 20.0;
+        draw_text(entry.title(), text_x, rect.y + 28.0, 28.0, WHITE);
+        let difficulty = match entry.difficulty {
+            _ if !entry.graded => "grading...".to_owned(),
+            Some(difficulty) => format!("{:?}", difficulty),
+            None => "no unique solution".to_owned(),
+        };
+        let mut details = format!("{}x{}, {}", entry.board.rows, entry.board.cols, difficulty);
+        if let Some(author) = &entry.info.author {
+            details += &format!(", by {}", author);
+        }
+        if let Some(best) = self.library.best_time(&entry.name) {
+            details += &format!(", best {}", format_time(best));
+        }
+        draw_text(&details, text_x, rect.y + 56.0, 22.0, GRAY);
+        let status = if completed {
+            Some(("completed", GREEN))
+        } else if self.library.has_progress(&entry.name) {
+            Some(("in progress", YELLOW))
+        } else {
+            None
+        };
+        if let Some((status, color)) = status {
+            draw_text(status, rect.x + rect.w - 130.0, rect.y + 28.0, 24.0, color);
+        }
+
+        draw_line(
+            rect.x,
+            rect.y + rect.h,
+            rect.x + rect.w,
+            rect.y + rect.h,
+            1.0,
+            DARKGRAY,
+        );
+    }
+
+    fn entry_rect(&self, i: usize) -> Rect {
+        let y = TOP + i as f32 * ROW_HEIGHT - self.scroll;
+        Rect::new(0.0, y, screen_width(), ROW_HEIGHT)
+    }
+}
+
+fn blank_button(i: usize) -> Rect {
+    Rect::new(210.0 + i as f32 * 90.0, 60.0, 80.0, 32.0)
+}
+
+// the filled cells of the board, scaled into a square of the size keeping its proportions
+pub fn draw_thumbnail(board: &Board, x: f32, y: f32, size: f32) {
+    let cell_size = size / board.rows.max(board.cols).max(1) as f32;
+    let (width, height) = (board.cols as f32 * cell_size, board.rows as f32 * cell_size);
+    let (x, y) = (
+        x + (size - width) / 2.0,
+        y + (size - height) / 2.0,
+    );
+
+    draw_rectangle(x, y, width, height, DARKGRAY);
+    for (n_row, row) in board.data.iter().enumerate() {
+        for (n_col, cell) in row.iter().enumerate() {
+            if *cell == Cell::On {
+                let (cell_x, cell_y) = (x + n_col as f32 * cell_size, y + n_row as f32 * cell_size);
+                draw_rectangle(cell_x, cell_y, cell_size, cell_size, GREEN);
+            }
+        }
+    }
+    draw_rectangle_lines(x, y, width, height, 1.0, GRAY);
+}
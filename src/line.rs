@@ -0,0 +1,85 @@
+use std::ops::{Index, IndexMut};
+
+use crate::board::{Cell, LineId};
+
+// a row, a col or any other run of cells the line rules can work on
+pub trait Line: Index<usize, Output = Cell> + IndexMut<usize> {
+    fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn get(&self, i: usize) -> Option<&Cell> {
+        if i < self.len() {
+            Some(&self[i])
+        } else {
+            None
+        }
+    }
+
+    fn get_mut(&mut self, i: usize) -> Option<&mut Cell> {
+        if i < self.len() {
+            Some(&mut self[i])
+        } else {
+            None
+        }
+    }
+
+    fn cells(&self) -> Vec<Cell> {
+        (0..self.len()).map(|i| self[i]).collect()
+    }
+
+    fn fill(&mut self, cell: Cell) {
+        for i in 0..self.len() {
+            self[i] = cell;
+        }
+    }
+}
+
+impl Line for [Cell] {
+    fn len(&self) -> usize {
+        <[Cell]>::len(self)
+    }
+}
+
+// a row or col of a grid, borrowed without copying the cells
+pub struct LineMut<'a> {
+    data: &'a mut [Vec<Cell>],
+    line: LineId,
+}
+
+impl<'a> LineMut<'a> {
+    pub fn new(data: &'a mut [Vec<Cell>], line: LineId) -> Self {
+        LineMut { data, line }
+    }
+}
+
+impl Index<usize> for LineMut<'_> {
+    type Output = Cell;
+
+    fn index(&self, i: usize) -> &Cell {
+        match self.line {
+            LineId::Row(n) => &self.data[n][i],
+            LineId::Col(n) => &self.data[i][n],
+        }
+    }
+}
+
+impl IndexMut<usize> for LineMut<'_> {
+    fn index_mut(&mut self, i: usize) -> &mut Cell {
+        match self.line {
+            LineId::Row(n) => &mut self.data[n][i],
+            LineId::Col(n) => &mut self.data[i][n],
+        }
+    }
+}
+
+impl Line for LineMut<'_> {
+    fn len(&self) -> usize {
+        match self.line {
+            LineId::Row(n) => self.data[n].len(),
+            LineId::Col(_) => self.data.len(),
+        }
+    }
+}
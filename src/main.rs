@@ -1,14 +1,30 @@
+use std::env;
 use std::io::Read;
 
 use itertools::Itertools;
 use macroquad::prelude::*;
 
-use crate::board::{Board, Cell, Rule};
+use nonogram_solver::analysis::{self, LineStatus};
+use nonogram_solver::board::{Board, Cell, LineId, GUIDE_EVERY};
+use nonogram_solver::json::JsonError;
+use nonogram_solver::library::{Library, PuzzleFile};
+use nonogram_solver::progress::Progress;
+use nonogram_solver::solver;
 
-mod board;
-mod nonogram_solver;
-mod solver;
-mod tests;
+use crate::camera::{Camera, HEADER_HEIGHT};
+use crate::completion_screen::{format_time, CompletionChoice, CompletionScreen};
+use crate::library_screen::{Choice, LibraryScreen};
+use crate::save_dialog::{DialogResult, SaveDialog};
+use crate::solver_worker::SolverWorker;
+
+mod camera;
+mod completion_screen;
+mod library_screen;
+mod save_dialog;
+mod solver_worker;
+
+const PUZZLE_DIR: &str = "puzzles";
+const AUTOSAVE_SECS: f64 = 5.0;
 
 #[derive(Debug, Eq, PartialEq)]
 pub enum Mode {
@@ -17,221 +33,670 @@ pub enum Mode {
 }
 
 struct Nonogram {
+    name: Option<String>,    // set for puzzles from the library
+    editing: Option<String>, // the library puzzle saving in Create mode replaces
+    solution: Option<Board>,
     create_board: Board,
     play_board: Board,
     is_solvable: bool,
     is_solved: bool,
     mode: Mode,
+    solver_worker: SolverWorker,
+    camera: Camera,
+    elapsed_secs: f64,
+    mistakes: usize,
+    moves: usize,
+    hints: usize,
+    finished: bool, // the completion screen was shown
+    unsaved: bool,
+    since_save: f64,
+    save_progress: bool, // false if a saved game couldn't be read, to keep it as it is
+    cursor: (usize, usize), // row and col of the cell the keys work on
+    line_statuses: Vec<LineStatus>, // of play_board, in the order of `Board::lines`
+    guide_every: usize,     // 0 for no guide lines
 }
 
 impl<'board> Nonogram {
     pub fn new(rows: usize, cols: usize) -> Self {
         Nonogram {
-            create_board: Board::new(rows, cols, vec![]),
-            play_board: Board::new(rows, cols, vec![]),
+            name: None,
+            editing: None,
+            solution: None,
+            create_board: Board::new(rows, cols),
+            play_board: Board::new(rows, cols),
             is_solvable: false,
             mode: Mode::Create,
             is_solved: false,
+            solver_worker: SolverWorker::new(),
+            camera: Camera::new(),
+            elapsed_secs: 0.0,
+            mistakes: 0,
+            moves: 0,
+            hints: 0,
+            finished: false,
+            unsaved: false,
+            since_save: 0.0,
+            save_progress: true,
+            cursor: (0, 0),
+            line_statuses: vec![],
+            guide_every: GUIDE_EVERY,
         }
     }
 
     pub fn play_with_board(board: Board) -> Self {
-        Nonogram {
-            create_board: Board::new(board.rows, board.cols, vec![]),
+        let mut game = Nonogram {
+            name: None,
+            editing: None,
+            solution: None,
+            create_board: Board::new(board.rows, board.cols),
             play_board: board,
             is_solvable: false,
             mode: Mode::Play,
             is_solved: false,
-        }
+            solver_worker: SolverWorker::new(),
+            camera: Camera::new(),
+            elapsed_secs: 0.0,
+            mistakes: 0,
+            moves: 0,
+            hints: 0,
+            finished: false,
+            unsaved: false,
+            since_save: 0.0,
+            save_progress: true,
+            cursor: (0, 0),
+            line_statuses: vec![],
+            guide_every: GUIDE_EVERY,
+        };
+        // whether the clues can be solved doesn't change while playing
+        game.solver_worker.request(game.play_board.clone());
+        game.update_statuses();
+        game
     }
 
     pub fn change_mode(&mut self) {
-        // let mut board_copy = self.board.clone();
-        // solver::solve(&mut board_copy, true);
-        // *self.board = board_copy;
         self.mode = match self.mode {
             Mode::Create => {
                 self.play_board.clear_board();
-                self.play_board.rules = self.create_board.rules.clone();
+                self.play_board.copy_clues(&self.create_board);
+                // a puzzle of its own now, not the one from the library
+                self.name = None;
+                self.solution = None;
+                self.is_solved = false;
+                self.finished = false;
+                self.elapsed_secs = 0.0;
+                self.mistakes = 0;
+                self.moves = 0;
+                self.hints = 0;
+                self.update_statuses();
+                self.solver_worker.request(self.play_board.clone());
 
                 Mode::Play
             }
-            Mode::Play => Mode::Create,
+            Mode::Play => {
+                self.solver_worker.request(self.create_board.clone());
+                Mode::Create
+            }
         }
+    }
 
+    fn update_statuses(&mut self) {
+        let lines = self.play_board.lines().map(|(line, _)| line).collect_vec();
+        self.line_statuses = lines.into_iter().map(|line| self.line_status(line)).collect();
     }
 
-    pub fn update(&mut self) {
-        let active_board = match self.mode {
-            Mode::Play => &mut self.play_board,
-            Mode::Create => &mut self.create_board,
-        };
+    fn line_status(&mut self, line: LineId) -> LineStatus {
+        let hints = self.play_board.clue(line).hints.clone();
+        analysis::line_status(&hints, &self.play_board.get_line(line))
+    }
 
-        let screen_size = screen_width().min(screen_height()) * 0.65f32;
-        let dimension = active_board.rows.max(active_board.cols);
-        let rect_size = screen_size / dimension as f32;
+    pub fn progress(&self) -> Progress {
+        Progress {
+            moves: self.moves,
+            hints: self.hints,
+            ..Progress::new(&self.play_board, self.elapsed_secs, self.mistakes)
+        }
+    }
 
-        let start_x = screen_width() - active_board.cols as f32 * rect_size;
-        let start_y = screen_height() - active_board.rows as f32 * rect_size;
+    pub fn resume(&mut self, progress: &Progress) -> Result<(), JsonError> {
+        progress.restore(&mut self.play_board)?;
+        self.elapsed_secs = progress.elapsed_secs;
+        self.mistakes = progress.mistakes;
+        self.moves = progress.moves;
+        self.hints = progress.hints;
+        self.is_solved = self.play_board.satisfies_clues();
+        self.update_statuses();
+        Ok(())
+    }
 
-        if is_mouse_button_pressed(MouseButton::Left) || is_mouse_button_pressed(MouseButton::Right)
-        {
-            let (mouse_x, mouse_y) = mouse_position();
-            let cell_x = (mouse_x - start_x) / rect_size;
-            let cell_y = (mouse_y - start_y) / rect_size;
-            let n_row = cell_y as usize;
-            let n_col = cell_x as usize;
-
-            let clicked = active_board
-                .data
-                .get_mut(n_row)
-                .and_then(|row| row.get_mut(n_col))
-                .and_then(|mut cell| {
-                    let res = if is_mouse_button_pressed(MouseButton::Right) {
-                        *cell = Cell::Off
-                    } else {
-                        *cell = cell.flip()
-                    };
-                    Some(res)
-                });
-            if clicked.is_some() {
-                if self.mode == Mode::Create {
-                    active_board.rules = active_board.generate_new_rules_according_to_board();
-                }
+    pub fn update(&mut self) {
+        if let Some(is_solvable) = self.solver_worker.poll() {
+            self.is_solvable = is_solvable;
+        }
 
-                let mut board_copy = active_board.clone();
-                self.is_solvable = solver::solve(&mut board_copy, true);
+        if self.mode == Mode::Play && !self.is_solved {
+            let frame_time = get_frame_time() as f64;
+            self.elapsed_secs += frame_time;
+            self.since_save += frame_time;
+        }
 
-                if self.mode == Mode::Play {
-                    self.is_solved = active_board.boards_are_equal(&board_copy);
-                }
+        let active_board = match self.mode {
+            Mode::Play => &self.play_board,
+            Mode::Create => &self.create_board,
+        };
+        self.camera.update(active_board);
+
+        if let Some((n_row, n_col, cell)) = self.input() {
+            if self.set_cell(n_row, n_col, cell) && self.mode == Mode::Play {
+                self.moves += 1;
             }
-        } else if is_key_pressed(KeyCode::S) {
+        } else if self.mode == Mode::Play && !self.is_solved && is_key_pressed(KeyCode::H) {
+            self.hint();
+        } else if is_key_pressed(KeyCode::L) && !(self.mode == Mode::Play && self.name.is_some()) {
+            // not for library puzzles, it would count as completing them
+            let active_board = self.active_board();
             let mut board_copy = active_board.clone();
             solver::solve(&mut board_copy, true);
             *active_board = board_copy;
+            if self.mode == Mode::Play {
+                self.is_solved = self.play_board.satisfies_clues();
+                self.update_statuses();
+            }
         } else if is_key_pressed(KeyCode::Space) {
             self.change_mode();
+        } else if is_key_pressed(KeyCode::G) {
+            // guides every 5 cells, every 10, none
+            self.guide_every = match self.guide_every {
+                0 => GUIDE_EVERY,
+                n if n == GUIDE_EVERY => GUIDE_EVERY * 2,
+                _ => 0,
+            };
+        } else if is_key_pressed(KeyCode::I) {
+            self.camera.labels = !self.camera.labels;
         }
     }
 
-    pub fn draw(&mut self) {
-        let active_board = match self.mode {
+    fn active_board(&mut self) -> &mut Board {
+        match self.mode {
             Mode::Play => &mut self.play_board,
             Mode::Create => &mut self.create_board,
+        }
+    }
+
+    // The cell to change and what to put there, from the mouse or the keys. Both move the cursor.
+    // Left click flips a cell and right click crosses it out. Arrows or WASD move the cursor,
+    // with Shift the cell it leaves is copied along the way. Z fills, X crosses out and C clears
+    // the cell under the cursor.
+    fn input(&mut self) -> Option<(usize, usize, Cell)> {
+        let board = match self.mode {
+            Mode::Play => &self.play_board,
+            Mode::Create => &self.create_board,
         };
 
-        let screen_size = screen_width().min(screen_height()) * 0.65f32;
-        let dimension = active_board.rows.max(active_board.cols);
-        let rect_size = screen_size / dimension as f32;
+        if is_mouse_button_pressed(MouseButton::Left) || is_mouse_button_pressed(MouseButton::Right)
+        {
+            let mouse = Vec2::from(mouse_position());
+            let (n_row, n_col) = self.camera.cell_at(board, mouse)?;
+            self.cursor = (n_row, n_col);
+            let cell = if is_mouse_button_pressed(MouseButton::Right) {
+                Cell::Off
+            } else {
+                board.data[n_row][n_col].flip()
+            };
+            return Some((n_row, n_col, cell));
+        }
+
+        let (n_row, n_col) = self.cursor;
+        let pressed = |keys: &[KeyCode]| keys.iter().any(|key| is_key_pressed(*key));
+        let moved = if pressed(&[KeyCode::Up, KeyCode::W]) {
+            Some((n_row.saturating_sub(1), n_col))
+        } else if pressed(&[KeyCode::Down, KeyCode::S]) {
+            Some(((n_row + 1).min(board.rows - 1), n_col))
+        } else if pressed(&[KeyCode::Left, KeyCode::A]) {
+            Some((n_row, n_col.saturating_sub(1)))
+        } else if pressed(&[KeyCode::Right, KeyCode::D]) {
+            Some((n_row, (n_col + 1).min(board.cols - 1)))
+        } else {
+            None
+        };
 
-        let start_x = screen_width() - active_board.cols as f32 * rect_size;
-        let start_y = screen_height() - active_board.rows as f32 * rect_size;
+        if let Some((to_row, to_col)) = moved {
+            self.cursor = (to_row, to_col);
+            self.camera.show_cell(board, to_row, to_col);
+            let painting = is_key_down(KeyCode::LeftShift) || is_key_down(KeyCode::RightShift);
+            return Some((to_row, to_col, board.data[n_row][n_col])).filter(|_| painting);
+        }
 
-        // write whether it is solvable or not
-        let solvable_text = if self.is_solvable {
-            "Board is solvable"
+        let cell = if pressed(&[KeyCode::Z]) {
+            Cell::On
+        } else if pressed(&[KeyCode::X]) {
+            Cell::Off
+        } else if pressed(&[KeyCode::C]) {
+            Cell::None
         } else {
-            "Board isn't solvable"
+            return None;
         };
-        let text_size = rect_size * 2f32;
-        let text_dimensions = measure_text(solvable_text, None, text_size as u16, 1.0f32);
-        draw_text(
-            solvable_text,
-            0_f32,
-            text_dimensions.height + text_size,
-            text_size,
-            WHITE,
-        );
-        let mode_str = format!("Mode: {:?}; Solved?: {:?}", self.mode, self.is_solved);
-        draw_text(
-            mode_str.as_str(),
-            0_f32,
-            text_dimensions.height,
-            text_size,
-            WHITE,
-        );
+        Some((n_row, n_col, cell))
+    }
+
+    // false if the cell already was like that
+    fn set_cell(&mut self, n_row: usize, n_col: usize, cell: Cell) -> bool {
+        let active_board = self.active_board();
+        if active_board.data[n_row][n_col] == cell {
+            return false;
+        }
+        active_board.data[n_row][n_col] = cell;
+
+        if self.mode == Mode::Create {
+            self.create_board.generate_clues_from_board();
+            self.solver_worker.request(self.create_board.clone());
+        } else {
+            self.is_solved = self.play_board.satisfies_clues();
+            self.line_statuses[n_row] = self.line_status(LineId::Row(n_row));
+            self.line_statuses[self.play_board.rows + n_col] = self.line_status(LineId::Col(n_col));
+
+            // a cell that the solution has the other way round
+            if let Some(solution) = &self.solution {
+                if cell != Cell::None && solution.data[n_row][n_col] != cell {
+                    self.mistakes += 1;
+                }
+            }
+            self.unsaved = true;
+        }
+        true
+    }
+
+    // fixes a wrong cell if there is one, otherwise sets the next cell the solver would find
+    fn hint(&mut self) {
+        let board = &self.play_board;
+        let wrong = self.solution.as_ref().and_then(|solution| {
+            let cells = (0..board.rows).cartesian_product(0..board.cols);
+            cells
+                .filter(|&(r, c)| board.data[r][c] != Cell::None)
+                .find(|&(r, c)| solution.data[r][c] != board.data[r][c])
+                .map(|(r, c)| (r, c, solution.data[r][c]))
+        });
+        let next = || {
+            let (_, trace) = solver::solve_with_trace(&mut board.clone(), false);
+            let found = trace.first().and_then(|step| step.cells.first());
+            found.map(|cell| (cell.row, cell.col, cell.cell))
+        };
+        // lines alone don't get any further
+        let guessed = || {
+            let solution = self.solution.as_ref()?;
+            let cells = (0..board.rows).cartesian_product(0..board.cols);
+            cells
+                .filter(|&(r, c)| board.data[r][c] == Cell::None)
+                .map(|(r, c)| (r, c, solution.data[r][c]))
+                .find(|&(_, _, cell)| cell != Cell::None)
+        };
+
+        if let Some((n_row, n_col, cell)) = wrong.or_else(next).or_else(guessed) {
+            self.cursor = (n_row, n_col);
+            self.camera.show_cell(&self.play_board, n_row, n_col);
+            self.set_cell(n_row, n_col, cell);
+            self.hints += 1;
+        }
+    }
+
+    pub fn draw(&mut self) {
+        let active_board = match self.mode {
+            Mode::Play => &mut self.play_board,
+            Mode::Create => &mut self.create_board,
+        };
+        let layout = self.camera.layout(active_board);
+        let size = layout.cell_size;
+        let (visible_rows, visible_cols) = layout.visible_cells(active_board);
 
         // draw board
-        for (n_row, row) in active_board.data.iter().enumerate() {
-            for (n_col, cell) in row.iter().enumerate() {
-                let size = rect_size;
-                let (x_offset, y_offset) = (n_col as f32 * size, n_row as f32 * size);
-                let (x, y) = (start_x + x_offset, start_y + y_offset);
+        for n_row in visible_rows.clone() {
+            for n_col in visible_cols.clone() {
+                let pos = layout.cell_pos(n_row, n_col);
+                let (x, y) = (pos.x, pos.y);
 
                 draw_rectangle_lines(x, y, size, size, 1f32, GRAY);
 
-                match cell {
+                match active_board.data[n_row][n_col] {
                     Cell::None => {}
                     Cell::On => draw_rectangle(x, y, size, size, GREEN),
                     Cell::Off => {
-                        // draw_rectangle(x, y, size, size, GRAY)
-                        draw_line(x, y, x + rect_size, y + rect_size, 1f32, GRAY);
-                        draw_line(x + rect_size, y, x, y + rect_size, 1f32, GRAY);
+                        draw_line(x, y, x + size, y + size, 1f32, GRAY);
+                        draw_line(x + size, y, x, y + size, 1f32, GRAY);
                     }
                 }
             }
         }
 
-        // draw edges
-        let line_thickness = 2.0f32;
-        draw_line(
-            start_x,
-            start_y,
-            screen_width(),
-            start_y,
-            line_thickness,
-            SKYBLUE,
-        ); // left edge
-        draw_line(
-            start_x,
-            start_y,
-            start_x,
-            screen_height(),
-            line_thickness,
-            SKYBLUE,
-        ); // top edge
+        let cursor = layout.cell_pos(self.cursor.0, self.cursor.1);
+        draw_rectangle_lines(cursor.x, cursor.y, size, size, 3f32, YELLOW);
+
+        // guide lines between the groups of rows and cols
+        let guide_every = self.guide_every;
+        let is_guide = |n: usize| guide_every > 0 && n.is_multiple_of(guide_every);
+        let grid_end = layout.cell_pos(active_board.rows, active_board.cols);
+        for n_row in visible_rows.clone().chain(visible_rows.end..=visible_rows.end) {
+            if is_guide(n_row) {
+                let y = layout.grid.y + size * n_row as f32;
+                draw_line(layout.grid.x, y, grid_end.x, y, 2f32, LIGHTGRAY);
+            }
+        }
+        for n_col in visible_cols.clone().chain(visible_cols.end..=visible_cols.end) {
+            if is_guide(n_col) {
+                let x = layout.grid.x + size * n_col as f32;
+                draw_line(x, layout.grid.y, x, grid_end.y, 2f32, LIGHTGRAY);
+            }
+        }
+
+        // the clues are drawn over the part of the grid scrolled under them
+        let (clues_width, clues_bottom) = (layout.row_clues_width, layout.col_clues_bottom);
+        draw_rectangle(0.0, HEADER_HEIGHT, clues_width, screen_height(), BLACK);
+        draw_rectangle(0.0, HEADER_HEIGHT, screen_width(), clues_bottom - HEADER_HEIGHT, BLACK);
+
+        // while playing, the clues show how far each line is from them
+        let statuses = match self.mode {
+            Mode::Play => &self.line_statuses[..],
+            Mode::Create => &[],
+        };
 
         // draw rules
-        for rule in active_board.rules.iter() {
-            for (i, num) in rule.hints.iter().rev().enumerate() {
-                let (x, y) = if rule.is_col {
+        for (n_line, (line, clue)) in active_board.lines().enumerate() {
+            let visible = if line.is_col() { &visible_cols } else { &visible_rows };
+            if !visible.contains(&line.n()) {
+                continue;
+            }
+
+            for (i, num) in clue.hints.iter().rev().enumerate() {
+                let (x, y) = if line.is_col() {
                     (
-                        start_x + rect_size * rule.n as f32,
-                        start_y - rect_size - i as f32 * rect_size,
+                        layout.grid.x + size * line.n() as f32,
+                        clues_bottom - size - i as f32 * size,
                     )
                 } else {
                     (
-                        start_x - rect_size - i as f32 * rect_size,
-                        start_y + rect_size * rule.n as f32,
+                        clues_width - size - i as f32 * size,
+                        layout.grid.y + size * line.n() as f32,
                     )
                 };
 
                 let num_str = num.to_string();
 
-                let text_dimensions = measure_text(num_str.as_str(), None, rect_size as u16, 1f32);
-                let TextDimensions {
-                    width,
-                    height,
-                    offset_y,
-                } = text_dimensions;
+                // hints of several digits are made smaller to fit their cell
+                let width = measure_text(num_str.as_str(), None, size as u16, 1f32).width;
+                let font_size = size.min(size * size * 0.9 / width.max(1.0));
+                let text_dimensions = measure_text(num_str.as_str(), None, font_size as u16, 1f32);
+                let TextDimensions { width, height, .. } = text_dimensions;
+
+                let text_x_offset = (size - width) / 2f32;
+                let text_y_offset = (size - height) / 2f32;
 
-                let text_x_offset = (rect_size - width) / 2f32;
-                let text_y_offset = (rect_size - height) / 2f32;
+                let color = match statuses.get(n_line) {
+                    Some(LineStatus::Contradiction) => RED,
+                    Some(LineStatus::Satisfied) => DARKGRAY,
+                    Some(LineStatus::Unfinished(done)) if done[clue.hints.len() - 1 - i] => GRAY,
+                    _ => BLUE,
+                };
 
-                draw_rectangle_lines(x, y, rect_size, rect_size, 0.5f32, WHITE);
+                draw_rectangle_lines(x, y, size, size, 0.5f32, WHITE);
                 draw_text(
                     num_str.as_str(),
                     x + text_x_offset,
-                    y + rect_size - text_y_offset,
-                    rect_size,
-                    BLUE,
+                    y + size - text_y_offset,
+                    font_size,
+                    color,
                 );
             }
         }
+
+        // numbers of the rows and cols, in the cells beyond the clues
+        if self.camera.labels {
+            let label = |n: usize, x: f32, y: f32| {
+                let text = (n + 1).to_string();
+                let width = measure_text(&text, None, size as u16, 1f32).width;
+                let font_size = (size * 0.6).min(size * size * 0.9 / width.max(1.0));
+                let width = measure_text(&text, None, font_size as u16, 1f32).width;
+                let y = y + (size + font_size * 0.6) / 2f32;
+                draw_text(&text, x + (size - width) / 2f32, y, font_size, DARKGRAY);
+            };
+            for n_row in visible_rows.clone() {
+                label(n_row, 0.0, layout.grid.y + size * n_row as f32);
+            }
+            for n_col in visible_cols.clone() {
+                label(n_col, layout.grid.x + size * n_col as f32, HEADER_HEIGHT);
+            }
+        }
+
+        // the row and col under the mouse, or under the cursor when the mouse isn't over the grid
+        let (hover_row, hover_col) = self
+            .camera
+            .cell_at(active_board, Vec2::from(mouse_position()))
+            .unwrap_or(self.cursor);
+        let highlight = Color::new(1.0, 1.0, 0.0, 0.15);
+        if visible_rows.contains(&hover_row) {
+            let y = layout.grid.y + size * hover_row as f32;
+            draw_rectangle(0.0, y, screen_width(), size, highlight);
+        }
+        if visible_cols.contains(&hover_col) {
+            let x = layout.grid.x + size * hover_col as f32;
+            draw_rectangle(x, HEADER_HEIGHT, size, screen_height() - HEADER_HEIGHT, highlight);
+        }
+
+        // draw edges
+        let line_thickness = 2.0f32;
+        draw_line(
+            clues_width,
+            clues_bottom,
+            screen_width(),
+            clues_bottom,
+            line_thickness,
+            SKYBLUE,
+        ); // top edge
+        draw_line(
+            clues_width,
+            clues_bottom,
+            clues_width,
+            screen_height(),
+            line_thickness,
+            SKYBLUE,
+        ); // left edge
+
+        // write whether it is solvable or not
+        draw_rectangle(0.0, 0.0, screen_width(), HEADER_HEIGHT, BLACK);
+        let solvable_text = if self.solver_worker.is_busy() {
+            "checking..."
+        } else if self.is_solvable {
+            "Board is solvable"
+        } else {
+            "Board isn't solvable"
+        };
+        let text_size = 24f32;
+        draw_text(solvable_text, 0_f32, text_size * 2f32, text_size, WHITE);
+        let mode_str = match self.mode {
+            Mode::Play => format!(
+                "Play  Time {}  Moves {}  Mistakes {}  Hints {}{}",
+                format_time(self.elapsed_secs),
+                self.moves,
+                self.mistakes,
+                self.hints,
+                if self.is_solved { "  Solved!" } else { "" }
+            ),
+            Mode::Create => "Create".to_owned(),
+        };
+        draw_text(mode_str.as_str(), 0_f32, text_size, text_size, WHITE);
+
+        // which clue blocks the runs through a filled cell probably are
+        if self.mode == Mode::Play && active_board.data[hover_row][hover_col] == Cell::On {
+            let runs = [
+                describe_run(active_board, LineId::Row(hover_row), hover_col),
+                describe_run(active_board, LineId::Col(hover_col), hover_row),
+            ];
+            draw_tooltip(&runs, layout.cell_pos(hover_row, hover_col) + vec2(size, size));
+        }
+    }
+}
+
+// "Row 3: run of 2, likely block 1 of 2 (2)" for the run of On cells through cell i of the line
+fn describe_run(board: &mut Board, line: LineId, i: usize) -> String {
+    let hints = board.clue(line).hints.clone();
+    let cells = board.get_line(line);
+    let (start, length) = Board::get_consecutive_regions(&cells, false, None)
+        .into_iter()
+        .find(|&(start, length)| (start..start + length).contains(&i))
+        .unwrap_or((i, 1));
+
+    let block = match analysis::likely_block(&hints, &cells, start, length) {
+        Some(j) => format!("likely block {} of {} ({})", j + 1, hints.len(), hints[j]),
+        None => "doesn't fit the clue".to_owned(),
+    };
+    let name = if line.is_col() { "Col" } else { "Row" };
+    format!("{} {}: run of {}, {}", name, line.n() + 1, length, block)
+}
+
+fn draw_tooltip(lines: &[String], at: Vec2) {
+    let font_size = 20.0;
+    let width = lines
+        .iter()
+        .map(|line| measure_text(line, None, font_size as u16, 1.0).width)
+        .fold(0.0, f32::max)
+        + 12.0;
+    let height = lines.len() as f32 * font_size + 8.0;
+
+    // moved back into the window near its edges
+    let x = at.x.min(screen_width() - width).max(0.0);
+    let y = at.y.min(screen_height() - height).max(0.0);
+    draw_rectangle(x, y, width, height, Color::new(0.0, 0.0, 0.0, 0.85));
+    draw_rectangle_lines(x, y, width, height, 1.0, SKYBLUE);
+    for (i, line) in lines.iter().enumerate() {
+        draw_text(line, x + 6.0, y + (i + 1) as f32 * font_size, font_size, WHITE);
     }
 }
 
+fn open_puzzle(library: &Library, name: String) -> Option<Nonogram> {
+    let entry = library.get(&name)?;
+    let mut game = Nonogram::play_with_board(entry.board.clone());
+    game.solution = entry.solution.clone(); // None until the library screen has graded it
+
+    let resumed = Progress::load(&library.progress_path(&name))
+        .map_err(|err| err.to_string())
+        .and_then(|progress| match progress {
+            Some(progress) => game.resume(&progress).map_err(|err| err.to_string()),
+            None => Ok(()),
+        });
+    if let Err(err) = resumed {
+        eprintln!("Can't resume {}, starting over without saving: {}", name, err);
+        game.save_progress = false;
+    }
+
+    game.name = Some(name);
+    Some(game)
+}
+
+// the picture of a saved puzzle in Create mode, saving it again replaces the puzzle
+fn edit_puzzle(library: &Library, name: String) -> Option<Nonogram> {
+    let picture = library.get(&name)?.picture.clone()?;
+    let mut game = Nonogram::new(picture.rows, picture.cols);
+    game.solver_worker.request(picture.clone());
+    game.create_board = picture;
+    game.editing = Some(name);
+    Some(game)
+}
+
+fn save_dialog_for(game: &Nonogram, library: &Library) -> SaveDialog {
+    let info = game
+        .editing
+        .as_ref()
+        .and_then(|name| library.get(name))
+        .map(|entry| entry.info.clone())
+        .unwrap_or_default();
+    let author = info
+        .author
+        .or_else(|| env::var("USER").ok())
+        .or_else(|| env::var("USERNAME").ok())
+        .unwrap_or_default();
+    SaveDialog::new(info.title.as_deref().unwrap_or(""), &author)
+}
+
+fn save_created(game: &mut Nonogram, library: &mut Library, dialog: &SaveDialog) {
+    let name = match &game.editing {
+        Some(name) => name.clone(),
+        None => library.name_for(&dialog.title),
+    };
+    let file = PuzzleFile::new(&game.create_board, &dialog.title, &dialog.author);
+    match library.save_puzzle(&name, file) {
+        Ok(()) => game.editing = Some(name),
+        Err(err) => eprintln!("Can't save {}: {}", name, err),
+    }
+}
+
+// saves the progress on a library puzzle after every move, every few seconds and when leaving it,
+// finish() forgets it once the puzzle is completed. A save that couldn't be resumed, e.g. from a
+// newer version, is never written over.
+fn autosave(game: &mut Nonogram, library: &mut Library, leaving: bool) {
+    let name = match &game.name {
+        Some(name) => name,
+        None => return,
+    };
+    if game.is_solved
+        || !game.save_progress
+        || game.mode != Mode::Play
+        || !(game.unsaved || leaving || game.since_save > AUTOSAVE_SECS)
+    {
+        return;
+    }
+
+    if let Err(err) = library.save_progress(name, &game.progress()) {
+        eprintln!("Can't save the progress on {}: {}", name, err);
+    }
+    game.unsaved = false;
+    game.since_save = 0.0;
+}
+
+// marks a library puzzle completed and keeps its best time
+fn finish(game: &mut Nonogram, library: &mut Library) -> CompletionScreen {
+    game.finished = true;
+    let mut screen = CompletionScreen {
+        picture: game.play_board.clone(),
+        elapsed_secs: game.elapsed_secs,
+        moves: game.moves,
+        mistakes: game.mistakes,
+        hints: game.hints,
+        best_secs: None,
+        new_best: false,
+        has_next: false,
+        in_library: false,
+    };
+    let name = match &game.name {
+        Some(name) => name,
+        None => return screen,
+    };
+    let same_clues = library.get(name).is_some_and(|entry| {
+        entry.board.row_clues() == game.play_board.row_clues()
+            && entry.board.col_clues() == game.play_board.col_clues()
+    });
+    if !same_clues {
+        return screen;
+    }
+
+    screen.best_secs = library.best_time(name);
+    let completed = library.mark_completed(name).and_then(|_| {
+        // the save of another version stays
+        if game.save_progress {
+            library.clear_progress(name)
+        } else {
+            Ok(())
+        }
+    });
+    if let Err(err) = completed {
+        eprintln!("Can't save the completion of {}: {}", name, err);
+    }
+    match library.record_time(name, game.elapsed_secs) {
+        Ok(new_best) => screen.new_best = new_best,
+        Err(err) => eprintln!("Can't save the time of {}: {}", name, err),
+    }
+    game.unsaved = false;
+    screen.has_next = library.next_puzzle(name).is_some();
+    screen.in_library = true;
+    screen
+}
+
 fn window_conf() -> Conf {
     Conf {
         window_title: "Window Conf".to_owned(),
@@ -244,27 +709,84 @@ fn window_conf() -> Conf {
 
 #[macroquad::main(window_conf)]
 async fn main() {
-    let mut board = nonogram_solver::run_nonogram_solver();
-    // // solver::solve(&mut board);
-    let mut nonogram = Nonogram::play_with_board(board);
-
-    // let mut board = Board::new(8, 8, vec![]);
-
-    // let mut nonogram = Nonogram::new(8, 8);
-
-
+    let library = Library::load(PUZZLE_DIR).unwrap_or_else(|err| {
+        eprintln!("Can't read the puzzles in {}: {}", PUZZLE_DIR, err);
+        Library::new(PUZZLE_DIR)
+    });
+    let mut library_screen = LibraryScreen::new(library);
+    let mut nonogram: Option<Nonogram> = None;
+    let mut save_dialog: Option<SaveDialog> = None;
+    let mut completion: Option<CompletionScreen> = None;
 
     loop {
         clear_background(BLACK);
-
-        // draw_line(40.0, 40.0, 100.0, 200.0, 15.0, BLUE);
-        // draw_rectangle_lines(screen_width() / 2.0 - 60.0, 100.0, 120.0, 60.0, 10.0, GREEN);
-        // draw_circle(screen_width() - 30.0, screen_height() - 30.0, 15.0, YELLOW);
-        //
-        // draw_text("1!", 20.0, 20.0, 30.0, DARKGRAY);
-
-        nonogram.update();
-        nonogram.draw();
+        library_screen.receive_grades();
+
+        match &mut nonogram {
+            Some(game) if save_dialog.is_some() => {
+                game.draw();
+                let dialog = save_dialog.as_mut().unwrap();
+                dialog.draw();
+                match dialog.update() {
+                    Some(DialogResult::Save) => {
+                        save_created(game, &mut library_screen.library, dialog);
+                        save_dialog = None;
+                    }
+                    Some(DialogResult::Cancel) => save_dialog = None,
+                    None => {}
+                }
+            }
+            Some(game) if completion.is_some() => {
+                game.draw();
+                let screen = completion.as_ref().unwrap();
+                screen.draw();
+                let choice = screen.update();
+                if choice.is_some() {
+                    completion = None;
+                }
+                let library = &library_screen.library;
+                match choice {
+                    Some(CompletionChoice::Next) => {
+                        let name = game.name.as_ref().and_then(|name| library.next_puzzle(name));
+                        nonogram = name.and_then(|entry| open_puzzle(library, entry.name.clone()));
+                    }
+                    Some(CompletionChoice::Library) => nonogram = None,
+                    Some(CompletionChoice::Back) | None => {}
+                }
+            }
+            Some(game) => {
+                // Enter in Create mode saves the drawn puzzle
+                if game.mode == Mode::Create && is_key_pressed(KeyCode::Enter) {
+                    save_dialog = Some(save_dialog_for(game, &library_screen.library));
+                }
+                // the solution of a puzzle opened before it was graded
+                if game.solution.is_none() {
+                    let library = &library_screen.library;
+                    let entry = game.name.as_ref().and_then(|name| library.get(name));
+                    game.solution = entry.and_then(|entry| entry.solution.clone());
+                }
+                game.update();
+                game.draw();
+
+                // back to the library
+                let leaving = is_key_pressed(KeyCode::Escape);
+                autosave(game, &mut library_screen.library, leaving);
+                if leaving {
+                    nonogram = None;
+                } else if game.mode == Mode::Play && game.is_solved && !game.finished {
+                    completion = Some(finish(game, &mut library_screen.library));
+                }
+            }
+            None => {
+                nonogram = match library_screen.update() {
+                    Some(Choice::Open(name)) => open_puzzle(&library_screen.library, name),
+                    Some(Choice::Edit(name)) => edit_puzzle(&library_screen.library, name),
+                    Some(Choice::NewBlank(size)) => Some(Nonogram::new(size, size)),
+                    None => None,
+                };
+                library_screen.draw();
+            }
+        }
 
         next_frame().await
     }
@@ -0,0 +1,123 @@
+// Progress on a puzzle, saved while it is played so it can be resumed later:
+//   {
+//     "version": 1,
+//     "grid": ["X. ", "  X"],  // cells as in the board JSON
+//     "elapsed_secs": 42.5,
+//     "mistakes": 1,
+//     "moves": 12,             // may be missing in older saves
+//     "hints": 0
+//   }
+// Saves of another version are rejected instead of being misread.
+
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::board::Board;
+use crate::json::{self, JsonError};
+
+pub const PROGRESS_VERSION: u32 = 1;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Progress {
+    pub version: u32,
+    pub grid: Vec<String>,
+    pub elapsed_secs: f64,
+    pub mistakes: usize,
+    #[serde(default)]
+    pub moves: usize,
+    #[serde(default)]
+    pub hints: usize,
+}
+
+#[derive(Debug)]
+pub enum ProgressError {
+    Io(io::Error),
+    Json(serde_json::Error),
+    UnsupportedVersion(u32),
+}
+
+impl fmt::Display for ProgressError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProgressError::Io(err) => write!(f, "{}", err),
+            ProgressError::Json(err) => write!(f, "{}", err),
+            ProgressError::UnsupportedVersion(version) => write!(
+                f,
+                "save version {} isn't supported, expected {}",
+                version, PROGRESS_VERSION
+            ),
+        }
+    }
+}
+
+impl From<io::Error> for ProgressError {
+    fn from(err: io::Error) -> Self {
+        ProgressError::Io(err)
+    }
+}
+
+impl From<serde_json::Error> for ProgressError {
+    fn from(err: serde_json::Error) -> Self {
+        ProgressError::Json(err)
+    }
+}
+
+impl Progress {
+    pub fn new(board: &Board, elapsed_secs: f64, mistakes: usize) -> Self {
+        Progress {
+            version: PROGRESS_VERSION,
+            grid: json::format_grid(board),
+            elapsed_secs,
+            mistakes,
+            moves: 0,
+            hints: 0,
+        }
+    }
+
+    // None if nothing was saved yet
+    pub fn load(path: &Path) -> Result<Option<Self>, ProgressError> {
+        let json = match fs::read_to_string(path) {
+            Ok(json) => json,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(err.into()),
+        };
+
+        #[derive(Deserialize)]
+        struct Version {
+            version: u32,
+        }
+        let Version { version } = serde_json::from_str(&json)?;
+        if version != PROGRESS_VERSION {
+            return Err(ProgressError::UnsupportedVersion(version));
+        }
+
+        Ok(Some(serde_json::from_str(&json)?))
+    }
+
+    // written to a temporary file first, so a crash never leaves half a save behind
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let tmp_path = path.with_extension("json.tmp");
+        fs::write(&tmp_path, serde_json::to_string_pretty(self)?)?;
+        fs::rename(&tmp_path, path)
+    }
+
+    pub fn clear(path: &Path) -> io::Result<()> {
+        match fs::remove_file(path) {
+            Err(err) if err.kind() != io::ErrorKind::NotFound => Err(err),
+            _ => Ok(()),
+        }
+    }
+
+    // puts the saved cells on the board, the clues stay as they are
+    pub fn restore(&self, board: &mut Board) -> Result<(), JsonError> {
+        board.data = json::parse_grid(&self.grid, board.rows, board.cols)?;
+        Ok(())
+    }
+}
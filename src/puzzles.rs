@@ -1,7 +1,104 @@
-use crate::board::{Board, Rule};
+use crate::board::{Board, Cell, Rule};
 
 pub fn run_nonogram_solver() -> Board {
-    let mut rules = vec![
+    // let board = puzzle_8x8();
+    // let board = puzzle_20x20();
+    let board = puzzle_20x30();
+    // board.data[2][1] = Cell::On;
+    // board.data[6][2] = Cell::Off;
+
+    // board.solve();
+    // board.print_board();
+
+    board
+
+    // println!("Rows: ");
+    // for n_row in 0..board.rows {
+    //     let row = board.get_row(n_row);
+    //     print!("{}| ", n_row + 1);
+    //     println!("{}", row.iter().map(|c| c.as_char()).collect::<String>());
+    // }
+    // println!();
+    // println!("Cols: ");
+    // for n_col in 0..board.cols {
+    //     let mut col = board.get_col(n_col);
+    //     *col[1] = Cell::On;
+    //     print!("{}| ", n_col + 1);
+    //     println!("{}", col.iter().map(|c| c.as_char()).collect::<String>());
+    // }
+}
+
+// the puzzles below, used by tests and benchmarks
+pub fn reference_puzzles() -> Vec<(&'static str, Board)> {
+    vec![
+        ("20x20", puzzle_20x20()),
+        ("8x8", puzzle_8x8()),
+        ("20x30", puzzle_20x30()),
+    ]
+}
+
+// unique puzzles the line rules get stuck on, they need guessing to finish
+pub fn hard_puzzles() -> Vec<(&'static str, Board)> {
+    vec![
+        ("hard 10x10", from_grid(&[
+            "X.X....X..",
+            "X..XX..X..",
+            ".X.XXX.X.X",
+            ".X.XXX..X.",
+            "..XX.X.X.X",
+            "X.X.....XX",
+            ".....X..XX",
+            "XXXXX.XX..",
+            ".X.XX.XXX.",
+            "....X.X..X",
+        ])),
+        ("hard 12x12", from_grid(&[
+            ".X..X.XXX.X.",
+            ".X.....X..X.",
+            ".XX.X.X..X.X",
+            "XX......XXX.",
+            "XX..XXX.X.XX",
+            "XXXXXXXXX.XX",
+            "X.X.X...XXX.",
+            "XX.XX.XX....",
+            "..X.XX.....X",
+            ".X..XX..XXX.",
+            "..X...XX.X.X",
+            "XX.XXX.X..X.",
+        ])),
+        ("hard 15x15", from_grid(&[
+            "XXX...X....XX.X",
+            "XX..X...XXX.XXX",
+            "XXX...X..X.XX..",
+            "XXXXX.XX...XXXX",
+            "X..X...XX.X...X",
+            "XX.XXX.XX......",
+            ".XXX.XX..XX.XXX",
+            "X.XXX....X.XX..",
+            "XXX.XX..X..X...",
+            "XX.XXXX..X.X.XX",
+            "X..XXXXX.X...X.",
+            ".....XXX...X...",
+            "..XXX....XXXX.X",
+            "X...XX..XXXX.XX",
+            "XX..X..X.XXXXXX",
+        ])),
+    ]
+}
+
+// a blank puzzle with the clues of a drawn grid
+fn from_grid(grid: &[&str]) -> Board {
+    let mut board = Board::new(grid.len(), grid[0].len());
+    for (row, line) in board.data.iter_mut().zip(grid) {
+        *row = line.chars().map(Cell::from_char).collect();
+    }
+    board.generate_clues_from_board();
+    board.clear_board();
+    board
+}
+
+fn puzzle_20x20() -> Board {
+    let rules = vec![
         // rows
         Rule::new(0, false, vec![3, 7, 4]),
         Rule::new(1, false, vec![3, 2, 5, 1]),
@@ -46,7 +143,11 @@ pub fn run_nonogram_solver() -> Board {
         Rule::new(19, true, vec![2, 2, 8, 2, 1]),
     ];
 
-    let rules2 = vec![
+    Board::from_rules(20, 20, rules).unwrap()
+}
+
+fn puzzle_8x8() -> Board {
+    let rules = vec![
         // rows
         Rule::new(0, false, vec![5]),
         Rule::new(1, false, vec![1, 1]),
@@ -67,7 +168,11 @@ pub fn run_nonogram_solver() -> Board {
         Rule::new(7, true, vec![]),
     ];
 
-    let rules_w = vec![
+    Board::from_rules(8, 8, rules).unwrap()
+}
+
+fn puzzle_20x30() -> Board {
+    let rules = vec![
         // rows
         Rule::new(0, false, vec![8,7,5,7]),
         Rule::new(1, false, vec![5,4,3,3]),
@@ -122,31 +227,5 @@ pub fn run_nonogram_solver() -> Board {
         Rule::new(29, true, vec![1]),
     ];
 
-    // let board_size = (8, 8);
-    // let board_size = (20, 20);
-    let board_size = (20, 30);
-
-    let mut board = Board::new(board_size.0, board_size.1, rules_w);
-    // board.data[2][1] = Cell::On;
-    // board.data[6][2] = Cell::Off;
-
-    // board.solve();
-    // board.print_board();
-
-    return board;
-
-    // println!("Rows: ");
-    // for n_row in 0..board.rows {
-    //     let row = board.get_row(n_row);
-    //     print!("{}| ", n_row + 1);
-    //     println!("{}", row.iter().map(|c| c.as_char()).collect::<String>());
-    // }
-    // println!();
-    // println!("Cols: ");
-    // for n_col in 0..board.cols {
-    //     let mut col = board.get_col(n_col);
-    //     *col[1] = Cell::On;
-    //     print!("{}| ", n_col + 1);
-    //     println!("{}", col.iter().map(|c| c.as_char()).collect::<String>());
-    // }
+    Board::from_rules(20, 30, rules).unwrap()
 }
@@ -0,0 +1,230 @@
+// Python extension module, built with `maturin develop` (see pyproject.toml) or
+//   cargo build --release --no-default-features --features python
+// and copying target/release/libnonogram_solver.so to nonogram_solver.so.
+//
+//   import nonogram_solver as ns
+//   board = ns.Board.from_grid(["X.X", "XXX"])  # clues are generated from the grid
+//   board.row_clues                            # [[1, 1], [3]]
+//   ns.solve(board)["status"]                  # "solved", board.grid is filled in
+//   ns.check_uniqueness(board)                 # "unique"
+
+use std::convert::TryFrom;
+
+use itertools::Itertools;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use serde::Serialize;
+
+use crate::analysis;
+use crate::board::{Board, Clue, Rule};
+use crate::json;
+use crate::solver;
+
+#[pyclass(name = "Rule")]
+#[derive(Clone)]
+struct PyRule {
+    #[pyo3(get, set)]
+    n: usize,
+    #[pyo3(get, set)]
+    is_col: bool,
+    #[pyo3(get, set)]
+    hints: Vec<usize>,
+}
+
+#[pymethods]
+impl PyRule {
+    #[new]
+    fn new(n: usize, is_col: bool, hints: Vec<usize>) -> Self {
+        PyRule { n, is_col, hints }
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Rule({}, {}, {:?})", self.n, py_bool(self.is_col), self.hints)
+    }
+}
+
+impl From<&Rule> for PyRule {
+    fn from(rule: &Rule) -> Self {
+        PyRule::new(rule.n, rule.is_col, rule.hints.clone())
+    }
+}
+
+impl From<&PyRule> for Rule {
+    fn from(rule: &PyRule) -> Self {
+        Rule::new(rule.n, rule.is_col, rule.hints.clone())
+    }
+}
+
+#[pyclass(name = "Board")]
+#[derive(Clone)]
+struct PyBoard {
+    board: Board,
+}
+
+#[pymethods]
+impl PyBoard {
+    // a blank board; clues are given per line, top to bottom and left to right
+    #[new]
+    #[pyo3(signature = (row_clues, col_clues, grid = None))]
+    fn new(
+        row_clues: Vec<Vec<usize>>, col_clues: Vec<Vec<usize>>, grid: Option<Vec<String>>,
+    ) -> PyResult<Self> {
+        let board_json = json::BoardJson {
+            rows: row_clues.len(),
+            cols: col_clues.len(),
+            row_clues,
+            col_clues,
+            grid: grid.unwrap_or_default(),
+        };
+        let board = Board::try_from(board_json).map_err(value_error)?;
+        Ok(PyBoard { board })
+    }
+
+    // a board with clues generated from a drawn grid
+    #[staticmethod]
+    fn from_grid(grid: Vec<String>) -> PyResult<Self> {
+        let rows = grid.len();
+        let cols = grid.first().map_or(0, |row| row.chars().count());
+        let mut board = Board::new(rows, cols);
+        board.data = json::parse_grid(&grid, rows, cols).map_err(value_error)?;
+        board.generate_clues_from_board();
+        Ok(PyBoard { board })
+    }
+
+    #[staticmethod]
+    fn from_json(text: &str) -> PyResult<Self> {
+        let board = serde_json::from_str(text).map_err(value_error)?;
+        Ok(PyBoard { board })
+    }
+
+    fn to_json(&self) -> String {
+        serde_json::to_string(&self.board).unwrap()
+    }
+
+    #[getter]
+    fn rows(&self) -> usize {
+        self.board.rows
+    }
+
+    #[getter]
+    fn cols(&self) -> usize {
+        self.board.cols
+    }
+
+    #[getter]
+    fn rules(&self) -> Vec<PyRule> {
+        self.board.rules().iter().map(PyRule::from).collect()
+    }
+
+    // every line needs exactly one rule, the known cells are kept
+    #[setter]
+    fn set_rules(&mut self, rules: Vec<PyRule>) -> PyResult<()> {
+        let rules = rules.iter().map(Rule::from).collect();
+        let board = Board::from_rules(self.board.rows, self.board.cols, rules)
+            .map_err(|errors| value_error(errors.iter().join("; ")))?;
+        self.board.copy_clues(&board);
+        Ok(())
+    }
+
+    #[getter]
+    fn row_clues(&self) -> Vec<Vec<usize>> {
+        hints(self.board.row_clues())
+    }
+
+    #[getter]
+    fn col_clues(&self) -> Vec<Vec<usize>> {
+        hints(self.board.col_clues())
+    }
+
+    // one string per row, cells as 'X' (filled), '.' (empty) or ' ' (unknown)
+    #[getter]
+    fn grid(&self) -> Vec<String> {
+        json::format_grid(&self.board)
+    }
+
+    #[setter]
+    fn set_grid(&mut self, grid: Vec<String>) -> PyResult<()> {
+        self.board.data =
+            json::parse_grid(&grid, self.board.rows, self.board.cols).map_err(value_error)?;
+        Ok(())
+    }
+
+    fn clear(&mut self) {
+        self.board.clear_board();
+    }
+
+    // replaces the clues with the ones matching the filled cells of the grid
+    fn generate_clues(&mut self) {
+        self.board.generate_clues_from_board();
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Board(rows={}, cols={})", self.board.rows, self.board.cols)
+    }
+}
+
+// solves the board in place, returns {"status": ..., "stats": {...}}
+#[pyfunction]
+#[pyo3(signature = (board, clear_board = true))]
+fn solve<'py>(
+    py: Python<'py>, board: &mut PyBoard, clear_board: bool,
+) -> PyResult<Bound<'py, PyDict>> {
+    let outcome = solver::solve(&mut board.board, clear_board);
+
+    let stats = PyDict::new(py);
+    stats.set_item("iterations", outcome.stats.iterations)?;
+    stats.set_item("lines_processed", outcome.stats.lines_processed)?;
+    stats.set_item("cells_solved", outcome.stats.cells_solved)?;
+
+    let result = PyDict::new(py);
+    result.set_item("status", snake_case(outcome.status))?;
+    result.set_item("stats", stats)?;
+    Ok(result)
+}
+
+// "unique", "multiple" or "no_solution"
+#[pyfunction]
+fn check_uniqueness(board: &PyBoard) -> String {
+    snake_case(analysis::check_uniqueness(&board.board))
+}
+
+// "easy", "medium", "hard" or None if the solution isn't unique
+#[pyfunction]
+fn grade_difficulty(board: &PyBoard) -> Option<String> {
+    analysis::grade_difficulty(&board.board).map(snake_case)
+}
+
+#[pymodule]
+fn nonogram_solver(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<PyRule>()?;
+    m.add_class::<PyBoard>()?;
+    m.add_function(wrap_pyfunction!(solve, m)?)?;
+    m.add_function(wrap_pyfunction!(check_uniqueness, m)?)?;
+    m.add_function(wrap_pyfunction!(grade_difficulty, m)?)?;
+    Ok(())
+}
+
+fn value_error<E: ToString>(err: E) -> PyErr {
+    PyValueError::new_err(err.to_string())
+}
+
+fn hints(clues: &[Clue]) -> Vec<Vec<usize>> {
+    clues.iter().map(|c| c.hints.clone()).collect()
+}
+
+fn py_bool(value: bool) -> &'static str {
+    if value {
+        "True"
+    } else {
+        "False"
+    }
+}
+
+// the name the value has in JSON
+fn snake_case<T: Serialize>(value: T) -> String {
+    serde_json::to_value(value)
+        .ok()
+        .and_then(|value| value.as_str().map(str::to_owned))
+        .unwrap_or_default()
+}
@@ -0,0 +1,372 @@
+// Boards drawn into images without a window, for thumbnails, snapshots of the solver and CI
+// artefacts. The clues are drawn left of and above the grid, with a small bitmap font scaled to
+// the cell size.
+// The steps of the line solver can be played back as frames, saved as an animated GIF or as
+// numbered PNG files.
+
+use std::borrow::Cow;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+use crate::board::{Board, Cell, Clue, GUIDE_EVERY};
+use crate::solver::TraceStep;
+
+pub type Rgb = [u8; 3];
+
+const BACKGROUND: Rgb = [255, 255, 255];
+const FILLED: Rgb = [32, 32, 32];
+const CROSSED: Rgb = [150, 150, 150];
+const GRID: Rgb = [190, 190, 190];
+const GUIDE: Rgb = [80, 80, 80];
+const CLUE: Rgb = [30, 60, 200];
+const ACTIVE_LINE: Rgb = [255, 236, 150]; // the line of a step
+const FIXED: Rgb = [210, 50, 40]; // the cells a step fixed
+const CAPTION: Rgb = [0, 0, 0];
+
+// every color of a frame, the palette of the GIFs
+const PALETTE: [Rgb; 9] = [
+    BACKGROUND,
+    FILLED,
+    CROSSED,
+    GRID,
+    GUIDE,
+    CLUE,
+    ACTIVE_LINE,
+    FIXED,
+    CAPTION,
+];
+
+// chars of 3x5 pixels, a row a byte with the leftmost pixel in bit 2
+const DIGITS: [[u8; 5]; 10] = [
+    [0b111, 0b101, 0b101, 0b101, 0b111],
+    [0b010, 0b110, 0b010, 0b010, 0b111],
+    [0b111, 0b001, 0b111, 0b100, 0b111],
+    [0b111, 0b001, 0b011, 0b001, 0b111],
+    [0b101, 0b101, 0b111, 0b001, 0b001],
+    [0b111, 0b100, 0b111, 0b001, 0b111],
+    [0b111, 0b100, 0b111, 0b101, 0b111],
+    [0b111, 0b001, 0b010, 0b010, 0b010],
+    [0b111, 0b101, 0b111, 0b101, 0b111],
+    [0b111, 0b101, 0b111, 0b001, 0b111],
+];
+
+#[derive(Clone, Debug)]
+pub struct RenderOptions {
+    pub cell_size: usize, // in pixels, the lines between the cells included
+    pub clues: bool,
+    pub guide_every: usize, // 0 for no guide lines
+}
+
+impl Default for RenderOptions {
+    fn default() -> Self {
+        RenderOptions {
+            cell_size: 16,
+            clues: true,
+            guide_every: GUIDE_EVERY,
+        }
+    }
+}
+
+// RGB pixels, row by row
+#[derive(Clone, Debug, PartialEq)]
+pub struct Image {
+    pub width: usize,
+    pub height: usize,
+    pub pixels: Vec<u8>,
+}
+
+impl Image {
+    pub fn new(width: usize, height: usize, color: Rgb) -> Self {
+        Image {
+            width,
+            height,
+            pixels: color.repeat(width * height),
+        }
+    }
+
+    pub fn pixel(&self, x: usize, y: usize) -> Rgb {
+        let i = (y * self.width + x) * 3;
+        [self.pixels[i], self.pixels[i + 1], self.pixels[i + 2]]
+    }
+
+    // the part of the rectangle inside the image
+    pub fn fill_rect(&mut self, x: usize, y: usize, width: usize, height: usize, color: Rgb) {
+        for row in y..(y + height).min(self.height) {
+            for col in x..(x + width).min(self.width) {
+                let i = (row * self.width + col) * 3;
+                self.pixels[i..i + 3].copy_from_slice(&color);
+            }
+        }
+    }
+
+    pub fn write_png<W: Write>(&self, writer: W) -> Result<(), png::EncodingError> {
+        let mut encoder = png::Encoder::new(writer, self.width as u32, self.height as u32);
+        encoder.set_color(png::ColorType::RGB);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder.write_header()?.write_image_data(&self.pixels)
+    }
+
+    pub fn save_png(&self, path: &Path) -> io::Result<()> {
+        let file = BufWriter::new(File::create(path)?);
+        self.write_png(file).map_err(io::Error::other)
+    }
+}
+
+pub fn render_board(board: &Board, options: &RenderOptions) -> Image {
+    draw_board(board, options, None)
+}
+
+// A frame of the puzzle before the first step and one after every step of the trace, with the
+// line of the step highlighted, the cells it fixed in red and a caption naming the technique.
+pub fn render_trace(board: &Board, trace: &[TraceStep], options: &RenderOptions) -> Vec<Image> {
+    let mut board = board.clone();
+    board.clear_board();
+
+    let start = draw_board(&board, options, None);
+    let mut frames = vec![with_caption(
+        start,
+        &format!("{} steps", trace.len()),
+        options,
+    )];
+    for (n, step) in trace.iter().enumerate() {
+        for cell in step.cells.iter() {
+            board.data[cell.row][cell.col] = cell.cell;
+        }
+        let caption = format!(
+            "{}/{} {} {}: {}",
+            n + 1,
+            trace.len(),
+            if step.is_col { "col" } else { "row" },
+            step.n + 1,
+            step.technique.name()
+        );
+        let frame = draw_board(&board, options, Some(step));
+        frames.push(with_caption(frame, &caption, options));
+    }
+    frames
+}
+
+// the frames play in a loop, the last one stays a while longer
+pub fn write_gif<W: Write>(
+    frames: &[Image], frame_ms: u16, writer: W,
+) -> Result<(), gif::EncodingError> {
+    let (width, height) = frames
+        .first()
+        .map_or((1, 1), |frame| (frame.width, frame.height));
+    let palette = PALETTE.concat();
+    let mut encoder = gif::Encoder::new(writer, width as u16, height as u16, &palette)?;
+    encoder.set_repeat(gif::Repeat::Infinite)?;
+
+    for (n, image) in frames.iter().enumerate() {
+        let indices = image
+            .pixels
+            .chunks(3)
+            .map(|rgb| PALETTE.iter().position(|c| c == rgb).unwrap_or(0) as u8)
+            .collect::<Vec<_>>();
+        let delay = if n + 1 == frames.len() {
+            frame_ms.saturating_mul(4)
+        } else {
+            frame_ms
+        };
+        let frame = gif::Frame {
+            width: image.width as u16,
+            height: image.height as u16,
+            delay: delay / 10,
+            buffer: Cow::Owned(indices),
+            ..gif::Frame::default()
+        };
+        encoder.write_frame(&frame)?;
+    }
+    Ok(())
+}
+
+pub fn save_gif(frames: &[Image], frame_ms: u16, path: &Path) -> io::Result<()> {
+    let file = BufWriter::new(File::create(path)?);
+    write_gif(frames, frame_ms, file).map_err(io::Error::other)
+}
+
+// `<prefix>-001.png`, `<prefix>-002.png`... returns the names of the files
+pub fn save_png_sequence(frames: &[Image], prefix: &str) -> io::Result<Vec<String>> {
+    let digits = frames.len().to_string().len().max(3);
+    let mut names = vec![];
+    for (n, frame) in frames.iter().enumerate() {
+        let name = format!("{}-{:0width$}.png", prefix, n + 1, width = digits);
+        frame.save_png(Path::new(&name))?;
+        names.push(name);
+    }
+    Ok(names)
+}
+
+fn draw_board(board: &Board, options: &RenderOptions, step: Option<&TraceStep>) -> Image {
+    let cell = options.cell_size.max(2);
+    let longest = |clues: &[Clue]| {
+        clues
+            .iter()
+            .map(|c| c.hints.len())
+            .max()
+            .unwrap_or(0)
+            .max(1)
+    };
+    let (row_hints, col_hints) = if options.clues {
+        (longest(board.row_clues()), longest(board.col_clues()))
+    } else {
+        (0, 0)
+    };
+
+    // one more pixel for the line closing the grid
+    let (grid_x, grid_y) = (row_hints * cell, col_hints * cell);
+    let mut image = Image::new(
+        grid_x + board.cols * cell + 1,
+        grid_y + board.rows * cell + 1,
+        BACKGROUND,
+    );
+
+    if let Some(step) = step {
+        let (x, y, width, height) = if step.is_col {
+            (grid_x + step.n * cell, grid_y, cell, board.rows * cell)
+        } else {
+            (grid_x, grid_y + step.n * cell, board.cols * cell, cell)
+        };
+        image.fill_rect(x, y, width, height, ACTIVE_LINE);
+    }
+
+    for (n_row, row) in board.data.iter().enumerate() {
+        for (n_col, c) in row.iter().enumerate() {
+            let (x, y) = (grid_x + n_col * cell, grid_y + n_row * cell);
+            let fixed = step.is_some_and(|step| {
+                step.cells
+                    .iter()
+                    .any(|fixed| (fixed.row, fixed.col) == (n_row, n_col))
+            });
+            match c {
+                Cell::On => {
+                    let color = if fixed { FIXED } else { FILLED };
+                    image.fill_rect(x + 1, y + 1, cell - 1, cell - 1, color);
+                }
+                Cell::Off => {
+                    let color = if fixed { FIXED } else { CROSSED };
+                    let dot = (cell / 5).max(1);
+                    let offset = (cell - dot).div_ceil(2);
+                    image.fill_rect(x + offset, y + offset, dot, dot, color);
+                }
+                Cell::None => {}
+            }
+        }
+    }
+
+    let is_guide = |n: usize, count: usize| {
+        n == 0 || n == count || (options.guide_every > 0 && n.is_multiple_of(options.guide_every))
+    };
+    let (grid_width, grid_height) = (board.cols * cell + 1, board.rows * cell + 1);
+    // the thin lines first, so the guides are drawn over them where they cross
+    for &guides in &[false, true] {
+        for n_row in 0..=board.rows {
+            if is_guide(n_row, board.rows) == guides {
+                let color = if guides { GUIDE } else { GRID };
+                image.fill_rect(grid_x, grid_y + n_row * cell, grid_width, 1, color);
+            }
+        }
+        for n_col in 0..=board.cols {
+            if is_guide(n_col, board.cols) == guides {
+                let color = if guides { GUIDE } else { GRID };
+                image.fill_rect(grid_x + n_col * cell, grid_y, 1, grid_height, color);
+            }
+        }
+    }
+
+    if options.clues {
+        for (n_row, clue) in board.row_clues().iter().enumerate() {
+            for (i, &hint) in clue.hints.iter().rev().enumerate() {
+                let x = grid_x - (i + 1) * cell;
+                draw_number(&mut image, hint, x, grid_y + n_row * cell, cell);
+            }
+        }
+        for (n_col, clue) in board.col_clues().iter().enumerate() {
+            for (i, &hint) in clue.hints.iter().rev().enumerate() {
+                let y = grid_y - (i + 1) * cell;
+                draw_number(&mut image, hint, grid_x + n_col * cell, y, cell);
+            }
+        }
+    }
+
+    image
+}
+
+// a strip below the image with the text in it, as big as fits
+fn with_caption(mut image: Image, text: &str, options: &RenderOptions) -> Image {
+    let text_width = (text.chars().count() * 4).max(1);
+    let scale = ((image.width.saturating_sub(4)) / text_width)
+        .min(options.cell_size / 6)
+        .max(1);
+    let height = 7 * scale;
+    image.pixels.extend(BACKGROUND.repeat(image.width * height));
+    image.height += height;
+
+    let top = image.height - 6 * scale;
+    draw_text(&mut image, text, 2, top, scale, CAPTION);
+    image
+}
+
+// centered in the cell at (x, y), as big as fits with a pixel of space around it
+fn draw_number(image: &mut Image, number: usize, x: usize, y: usize, cell: usize) {
+    let text = number.to_string();
+    let width = text.len() * 4 - 1; // with a column of space between the digits
+    let scale = ((cell - 2) / width).min((cell - 2) / 5).max(1);
+
+    let left = x + cell.saturating_sub(width * scale) / 2;
+    let top = y + cell.saturating_sub(5 * scale) / 2;
+    draw_text(image, &text, left, top, scale, CLUE);
+}
+
+// in capitals, chars without a glyph are left blank
+fn draw_text(image: &mut Image, text: &str, left: usize, top: usize, scale: usize, color: Rgb) {
+    for (n, ch) in text.chars().enumerate() {
+        for (row, bits) in glyph(ch.to_ascii_uppercase()).iter().enumerate() {
+            for col in 0..3 {
+                if bits & (0b100 >> col) != 0 {
+                    let x = left + (n * 4 + col) * scale;
+                    image.fill_rect(x, top + row * scale, scale, scale, color);
+                }
+            }
+        }
+    }
+}
+
+fn glyph(ch: char) -> [u8; 5] {
+    match ch {
+        '0'..='9' => DIGITS[ch as usize - '0' as usize],
+        'A' => [0b010, 0b101, 0b111, 0b101, 0b101],
+        'B' => [0b110, 0b101, 0b110, 0b101, 0b110],
+        'C' => [0b011, 0b100, 0b100, 0b100, 0b011],
+        'D' => [0b110, 0b101, 0b101, 0b101, 0b110],
+        'E' => [0b111, 0b100, 0b110, 0b100, 0b111],
+        'F' => [0b111, 0b100, 0b110, 0b100, 0b100],
+        'G' => [0b011, 0b100, 0b101, 0b101, 0b011],
+        'H' => [0b101, 0b101, 0b111, 0b101, 0b101],
+        'I' => [0b111, 0b010, 0b010, 0b010, 0b111],
+        'J' => [0b001, 0b001, 0b001, 0b101, 0b010],
+        'K' => [0b101, 0b101, 0b110, 0b101, 0b101],
+        'L' => [0b100, 0b100, 0b100, 0b100, 0b111],
+        'M' => [0b101, 0b111, 0b111, 0b101, 0b101],
+        'N' => [0b110, 0b101, 0b101, 0b101, 0b101],
+        'O' => [0b010, 0b101, 0b101, 0b101, 0b010],
+        'P' => [0b110, 0b101, 0b110, 0b100, 0b100],
+        'Q' => [0b010, 0b101, 0b101, 0b110, 0b011],
+        'R' => [0b110, 0b101, 0b110, 0b101, 0b101],
+        'S' => [0b011, 0b100, 0b010, 0b001, 0b110],
+        'T' => [0b111, 0b010, 0b010, 0b010, 0b010],
+        'U' => [0b101, 0b101, 0b101, 0b101, 0b111],
+        'V' => [0b101, 0b101, 0b101, 0b101, 0b010],
+        'W' => [0b101, 0b101, 0b111, 0b111, 0b101],
+        'X' => [0b101, 0b101, 0b010, 0b101, 0b101],
+        'Y' => [0b101, 0b101, 0b010, 0b010, 0b010],
+        'Z' => [0b111, 0b001, 0b010, 0b100, 0b111],
+        ':' => [0b000, 0b010, 0b000, 0b010, 0b000],
+        '/' => [0b001, 0b001, 0b010, 0b100, 0b100],
+        '-' => [0b000, 0b000, 0b111, 0b000, 0b000],
+        '.' => [0b000, 0b000, 0b000, 0b000, 0b010],
+        ',' => [0b000, 0b000, 0b000, 0b010, 0b100],
+        _ => [0; 5],
+    }
+}
@@ -0,0 +1,90 @@
+use macroquad::prelude::*;
+
+pub enum DialogResult {
+    Save,
+    Cancel,
+}
+
+// Asks for the title and author of a puzzle drawn in Create mode.
+// Tab switches between the fields, Enter saves, Escape cancels.
+pub struct SaveDialog {
+    pub title: String,
+    pub author: String,
+    editing_author: bool,
+}
+
+impl SaveDialog {
+    pub fn new(title: &str, author: &str) -> Self {
+        // macroquad keeps every char typed so far, drop the ones typed before the dialog opened
+        while get_char_pressed().is_some() {}
+
+        SaveDialog {
+            title: title.to_owned(),
+            author: author.to_owned(),
+            editing_author: false,
+        }
+    }
+
+    pub fn update(&mut self) -> Option<DialogResult> {
+        let field = if self.editing_author {
+            &mut self.author
+        } else {
+            &mut self.title
+        };
+        // the chars of a frame come newest first
+        let mut typed = vec![];
+        while let Some(ch) = get_char_pressed() {
+            typed.push(ch);
+        }
+        field.extend(typed.into_iter().rev().filter(|ch| !ch.is_control()));
+
+        if is_key_pressed(KeyCode::Backspace) {
+            field.pop();
+        } else if is_key_pressed(KeyCode::Tab) {
+            self.editing_author = !self.editing_author;
+        } else if is_key_pressed(KeyCode::Enter) {
+            return Some(DialogResult::Save);
+        } else if is_key_pressed(KeyCode::Escape) {
+            return Some(DialogResult::Cancel);
+        }
+        None
+    }
+
+    pub fn draw(&self) {
+        let (width, height) = (500.0, 190.0);
+        let (x, y) = (
+            (screen_width() - width) / 2.0,
+            (screen_height() - height) / 2.0,
+        );
+        draw_rectangle(x, y, width, height, BLACK);
+        draw_rectangle_lines(x, y, width, height, 2.0, SKYBLUE);
+
+        draw_text("Save puzzle", x + 20.0, y + 40.0, 32.0, WHITE);
+        let fields = [
+            ("Title", &self.title, false),
+            ("Author", &self.author, true),
+        ];
+        for (i, (label, value, is_author)) in fields.iter().enumerate() {
+            let field_y = y + 85.0 + i as f32 * 40.0;
+            let color = if *is_author == self.editing_author {
+                YELLOW
+            } else {
+                GRAY
+            };
+            draw_text(
+                &format!("{}: {}", label, value),
+                x + 20.0,
+                field_y,
+                28.0,
+                color,
+            );
+        }
+        draw_text(
+            "Tab: next field, Enter: save, Escape: cancel",
+            x + 20.0,
+            y + height - 15.0,
+            20.0,
+            GRAY,
+        );
+    }
+}
@@ -0,0 +1,148 @@
+// Local HTTP service around the solver. Every endpoint takes a POST with a JSON body and
+// answers with JSON, see `json.rs` for the board format.
+//
+//   POST /solve       board -> solve report
+//   POST /uniqueness  board -> { "uniqueness": "unique" | "multiple" | "no_solution" | "unknown" }
+//   POST /difficulty  board -> { "difficulty": "easy" | "medium" | "hard" | null | "unknown" }
+//   POST /clues       { "grid": ["X.", ".X"] } -> board with clues matching the grid
+//   POST /trace       board -> { "status", "stats", "steps": [trace step, ...] }
+//
+// Errors are answered with a 4xx status and { "error": "..." }. Bodies of more than
+// MAX_BODY_BYTES and boards of more than MAX_CELLS cells are answered with 413. The searching
+// endpoints answer "unknown" when the search runs out of SEARCH_NODES.
+
+use std::convert::TryFrom;
+use std::io::{self, Read};
+use std::net::ToSocketAddrs;
+
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tiny_http::{Header, Method, Response, Server};
+
+use crate::analysis;
+use crate::board::Board;
+use crate::json::{BoardJson, SolveReport};
+use crate::solver::{self, SolveStats, SolveStatus, TraceStep};
+
+pub const MAX_BODY_BYTES: usize = 1 << 20;
+pub const MAX_CELLS: usize = 50 * 50;
+pub const SEARCH_NODES: usize = 20_000;
+
+#[derive(Deserialize)]
+struct GridRequest {
+    grid: Vec<String>,
+}
+
+#[derive(Serialize)]
+struct TraceReport {
+    status: SolveStatus,
+    stats: SolveStats,
+    steps: Vec<TraceStep>,
+}
+
+pub fn bind<A: ToSocketAddrs>(addr: A) -> io::Result<Server> {
+    Server::http(addr).map_err(io::Error::other)
+}
+
+// answers requests until the server is unblocked or fails
+pub fn serve(server: &Server) {
+    for mut request in server.incoming_requests() {
+        let mut body = String::new();
+        let limit = MAX_BODY_BYTES as u64 + 1;
+        let read = request.as_reader().take(limit).read_to_string(&mut body);
+        let (status, json) = match read {
+            Ok(_) if body.len() > MAX_BODY_BYTES => {
+                let message = format!("bodies may have up to {} bytes", MAX_BODY_BYTES);
+                (413, error_json(&message))
+            }
+            Ok(_) => handle(request.method(), request.url(), &body),
+            Err(err) => (400, error_json(&err.to_string())),
+        };
+
+        let content_type = Header::from_bytes("Content-Type", "application/json").unwrap();
+        let response = Response::from_string(json)
+            .with_status_code(status)
+            .with_header(content_type);
+        let _ = request.respond(response);
+    }
+}
+
+pub fn handle(method: &Method, url: &str, body: &str) -> (u16, String) {
+    let path = url.split('?').next().unwrap_or(url);
+    let endpoints = ["/solve", "/uniqueness", "/difficulty", "/clues", "/trace"];
+    if !endpoints.contains(&path) {
+        return (404, error_json(&format!("unknown endpoint {}", path)));
+    }
+    if method != &Method::Post {
+        return (405, error_json(&format!("{} expects POST", path)));
+    }
+
+    // the size is checked before any cells are allocated
+    let board_json = match path {
+        "/clues" => serde_json::from_str::<GridRequest>(body).map(grid_board),
+        _ => serde_json::from_str::<BoardJson>(body),
+    };
+    let board_json = match board_json {
+        Ok(board_json) => board_json,
+        Err(err) => return (400, error_json(&err.to_string())),
+    };
+    if board_json.rows.saturating_mul(board_json.cols) > MAX_CELLS {
+        return (413, error_json(&format!("boards may have up to {} cells", MAX_CELLS)));
+    }
+
+    let result = Board::try_from(board_json)
+        .map_err(|err| err.to_string())
+        .map(|board| analyse(path, board));
+
+    match result {
+        Ok(value) => (200, value.to_string()),
+        Err(err) => (400, error_json(&err)),
+    }
+}
+
+fn analyse(path: &str, mut board: Board) -> serde_json::Value {
+    match path {
+        "/solve" => {
+            let outcome = solver::solve(&mut board, false);
+            json!(SolveReport::new(outcome, board))
+        }
+        "/uniqueness" => match analysis::check_uniqueness_within(&board, SEARCH_NODES) {
+            Ok(uniqueness) => json!({ "uniqueness": uniqueness }),
+            Err(_) => json!({ "uniqueness": "unknown" }),
+        },
+        "/difficulty" => match analysis::grade_difficulty_within(&board, SEARCH_NODES) {
+            Ok(difficulty) => json!({ "difficulty": difficulty }),
+            Err(_) => json!({ "difficulty": "unknown" }),
+        },
+        "/trace" => {
+            let (outcome, steps) = solver::solve_with_trace(&mut board, false);
+            json!(TraceReport {
+                status: outcome.status,
+                stats: outcome.stats,
+                steps,
+            })
+        }
+        "/clues" => {
+            board.generate_clues_from_board();
+            json!(board)
+        }
+        _ => unreachable!(),
+    }
+}
+
+// a board without clues yet, the grid says how big it is
+fn grid_board(request: GridRequest) -> BoardJson {
+    let rows = request.grid.len();
+    let cols = request.grid.first().map_or(0, |row| row.chars().count());
+    BoardJson {
+        rows,
+        cols,
+        row_clues: vec![vec![]; rows],
+        col_clues: vec![vec![]; cols],
+        grid: request.grid,
+    }
+}
+
+fn error_json(message: &str) -> String {
+    json!({ "error": message }).to_string()
+}
@@ -1,40 +1,174 @@
-use crate::board::{Board, Cell, Rule};
+use serde::{Deserialize, Serialize};
 
-pub fn solve(board: &mut Board, clear_board: bool) -> bool {
-    if clear_board {
-        board.clear_board();
+use crate::board::{Board, Cell, Clue};
+use crate::line::Line;
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SolveStatus {
+    Solved,       // every cell is known
+    Unsolved,     // line rules reached a fixed point with unknown cells left
+    InvalidClues, // the clues don't pass `Board::validate`, the board is left untouched
+}
+
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct SolveStats {
+    pub iterations: usize,      // passes over all lines until the fixed point
+    pub lines_processed: usize, // rows and cols the line rules were applied to
+    pub cells_solved: usize,    // cells that went from None to On/Off
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct SolveOutcome {
+    pub status: SolveStatus,
+    pub stats: SolveStats,
+}
+
+impl SolveOutcome {
+    pub fn is_solved(&self) -> bool {
+        self.status == SolveStatus::Solved
     }
+}
 
-    let mut rules = board.rules.clone();
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Technique {
+    SimpleBoxes,
+    CompleteLine,
+    FirstAndLastBlock,
+    ImpossibleRegion,
+    EmptyLine,
+}
 
-    loop {
-        let previous_board = board.data.clone();
-        for rule in rules.iter() {
-            let mut col_or_row = if rule.is_col {
-                board.get_col(rule.n)
-            } else {
-                board.get_row(rule.n)
-            };
+impl Technique {
+    // in the order they are applied to every line
+    pub const ALL: [Technique; 5] = [
+        Technique::SimpleBoxes,
+        Technique::CompleteLine,
+        Technique::FirstAndLastBlock,
+        Technique::ImpossibleRegion,
+        Technique::EmptyLine,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Technique::SimpleBoxes => "Simple boxes",
+            Technique::CompleteLine => "Complete line",
+            Technique::FirstAndLastBlock => "First and last block",
+            Technique::ImpossibleRegion => "Impossible region",
+            Technique::EmptyLine => "Empty line",
+        }
+    }
 
+    fn apply<L: Line + ?Sized>(&self, clue: &Clue, col_or_row: &mut L) {
+        match self {
             // mark overlapping blocks
-            simple_boxes(&rule.hints, &mut col_or_row);
+            Technique::SimpleBoxes => simple_boxes(&clue.hints, col_or_row),
+            // if all hints are complete, mark other cells as OFF
+            Technique::CompleteLine => mark_complete_row(clue, col_or_row),
+            Technique::FirstAndLastBlock => {
+                fill_first_and_last_block_if_possible(&clue.hints, col_or_row)
+            }
+            // if region is smaller that any hint, mark it as OFF
+            Technique::ImpossibleRegion => cross_impossible_region(&clue.hints, col_or_row),
+            Technique::EmptyLine => {
+                if clue.hints.is_empty() {
+                    col_or_row.fill(Cell::Off);
+                }
+            }
+        }
+    }
+}
 
-            // // fill best (if only one) region for a hint
-            // Board::fill_only_one_possible_hint_region(rule, &mut col_or_row);
+// cells fixed by applying one technique to one line
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct TraceStep {
+    pub is_col: bool,
+    pub n: usize,
+    pub technique: Technique,
+    pub cells: Vec<TraceCell>,
+}
 
-            // if all hints are complete, mark other cells as OFF
-            mark_complete_row(rule, &mut col_or_row);
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct TraceCell {
+    pub row: usize,
+    pub col: usize,
+    pub cell: Cell,
+}
 
-            fill_first_and_last_block_if_possible(&rule.hints, &mut col_or_row);
+pub fn solve(board: &mut Board, clear_board: bool) -> SolveOutcome {
+    run_line_rules(board, clear_board, None)
+}
 
-            // // enclose longest completed region with OFF cells
-            // Board::enclose_completed_region(rule, &mut col_or_row);
+pub fn solve_with_trace(board: &mut Board, clear_board: bool) -> (SolveOutcome, Vec<TraceStep>) {
+    let mut trace = vec![];
+    let outcome = run_line_rules(board, clear_board, Some(&mut trace));
+    (outcome, trace)
+}
 
-            // if region is smaller that any hint, mark it as OFF
-            cross_impossible_region(&rule.hints, &mut col_or_row);
+fn run_line_rules(
+    board: &mut Board, clear_board: bool, mut trace: Option<&mut Vec<TraceStep>>,
+) -> SolveOutcome {
+    if !board.validate().is_empty() {
+        return SolveOutcome {
+            status: SolveStatus::InvalidClues,
+            stats: SolveStats::default(),
+        };
+    }
 
-            if rule.hints.is_empty() {
-                col_or_row.iter_mut().for_each(|cell| **cell = Cell::Off);
+    if clear_board {
+        board.clear_board();
+    }
+
+    let lines = board
+        .lines()
+        .map(|(line, clue)| (line, clue.clone()))
+        .collect::<Vec<_>>();
+    let unknown_cells_before = count_unknown_cells(board);
+    let mut stats = SolveStats::default();
+
+    loop {
+        stats.iterations += 1;
+        let previous_board = board.data.clone();
+        for (line, clue) in lines.iter() {
+            stats.lines_processed += 1;
+            let mut col_or_row = board.get_line(*line);
+
+            // // fill best (if only one) region for a hint
+            // Board::fill_only_one_possible_hint_region(clue, &mut col_or_row);
+
+            // // enclose longest completed region with OFF cells
+            // Board::enclose_completed_region(clue, &mut col_or_row);
+
+            for technique in Technique::ALL.iter() {
+                let line_before = trace.as_ref().map(|_| col_or_row.cells());
+
+                technique.apply(clue, &mut col_or_row);
+
+                if let (Some(trace), Some(line_before)) = (trace.as_mut(), line_before) {
+                    let cells = line_before
+                        .iter()
+                        .zip(col_or_row.cells())
+                        .enumerate()
+                        .filter(|(_, (before, after))| **before != *after)
+                        .map(|(i, (_, after))| {
+                            let (row, col) = if line.is_col() {
+                                (i, line.n())
+                            } else {
+                                (line.n(), i)
+                            };
+                            TraceCell { row, col, cell: after }
+                        })
+                        .collect::<Vec<_>>();
+                    if !cells.is_empty() {
+                        trace.push(TraceStep {
+                            is_col: line.is_col(),
+                            n: line.n(),
+                            technique: *technique,
+                            cells,
+                        });
+                    }
+                }
             }
         }
 
@@ -45,7 +179,14 @@ pub fn solve(board: &mut Board, clear_board: bool) -> bool {
 
     // board.print_board();
 
-    return is_solved(board);
+    stats.cells_solved = unknown_cells_before - count_unknown_cells(board);
+    let status = if is_solved(board) {
+        SolveStatus::Solved
+    } else {
+        SolveStatus::Unsolved
+    };
+
+    SolveOutcome { status, stats }
 }
 
 pub fn is_solved(board: &Board) -> bool {
@@ -57,65 +198,72 @@ pub fn is_solved(board: &Board) -> bool {
     true
 }
 
-pub fn mark_complete_row(rule: &Rule, col_or_row: &mut Vec<&mut Cell>) {
-    let row_is_complete = rule.hints.iter().copied().sum::<usize>()
-        == col_or_row.iter().filter(|c| matches!(c, Cell::On)).count();
+fn count_unknown_cells(board: &Board) -> usize {
+    board.data.iter().flatten().filter(|c| **c == Cell::None).count()
+}
+
+pub fn mark_complete_row<L: Line + ?Sized>(clue: &Clue, col_or_row: &mut L) {
+    let row_is_complete = clue.hints.iter().copied().sum::<usize>()
+        == (0..col_or_row.len()).filter(|&i| col_or_row[i] == Cell::On).count();
     if row_is_complete {
-        for cell in col_or_row.iter_mut() {
-            if let Cell::None = cell {
-                **cell = Cell::Off;
+        for i in 0..col_or_row.len() {
+            if let Cell::None = col_or_row[i] {
+                col_or_row[i] = Cell::Off;
             }
         }
     }
 }
 
-pub fn enclose_completed_region(rule: &Rule, col_or_row: &mut Vec<&mut Cell>) {
+pub fn enclose_completed_region<L: Line + ?Sized>(clue: &Clue, col_or_row: &mut L) {
     // check region at row start and row end
     // if Some(&&mut Cell::On) = col_or_row.first() {
     //
     // }
 
     // check maximum hint region
-    if let Some(max_hint) = rule.hints.iter().copied().max() {
+    if let Some(max_hint) = clue.hints.iter().copied().max() {
         // find max consecutive region of size 'max_hint'
-        let consecutive_on_cells = Board::get_consecutive_regions(&col_or_row, false, None);
+        let consecutive_on_cells = Board::get_consecutive_regions(col_or_row, false, None);
         if let Some(&(reg_start, reg_len)) = consecutive_on_cells.iter().find(|&&r| r.1 == max_hint)
         {
             let reg_end = reg_start + reg_len - 1;
             if reg_start > 0 {
-                *col_or_row[reg_start - 1] = Cell::Off;
+                col_or_row[reg_start - 1] = Cell::Off;
             }
             if reg_end < col_or_row.len() - 1 {
-                *col_or_row[reg_end + 1] = Cell::Off;
+                col_or_row[reg_end + 1] = Cell::Off;
             }
         }
     }
 }
 
-pub fn cross_impossible_region(hints: &Vec<usize>, row: &mut Vec<&mut Cell>) {
+pub fn cross_impossible_region<L: Line + ?Sized>(hints: &[usize], row: &mut L) {
     let ((start, end), hints) = trim_finished_hints(hints, row);
     if hints.is_empty() {
         return;
     }
 
-    let mut regions =
-        Board::get_consecutive_regions(&row, true, Some((start as usize, end as usize)));
+    if start > end {
+        return;
+    }
+
+    let regions = Board::get_consecutive_regions(row, true, Some((start as usize, end as usize)));
 
     if let Some(&smallest_hint) = hints.iter().min() {
         for &(reg_pos, reg_len) in regions.iter() {
             if reg_len < smallest_hint {
                 for i in reg_pos..reg_pos + reg_len {
-                    *row[i] = Cell::Off;
+                    row[i] = Cell::Off;
                 }
             }
         }
     }
 }
 
-pub fn fill_only_one_possible_hint_region(rule: &Rule, mut col_or_row: &mut Vec<&mut Cell>) {
-    let mut open_regions = Board::get_consecutive_regions(&col_or_row, true, None);
+pub fn fill_only_one_possible_hint_region<L: Line + ?Sized>(clue: &Clue, col_or_row: &mut L) {
+    let open_regions = Board::get_consecutive_regions(col_or_row, true, None);
 
-    for &hint in rule.hints.iter() {
+    for &hint in clue.hints.iter() {
         let good_regions = open_regions
             .iter()
             .filter(|&&region| hint <= region.1)
@@ -126,45 +274,47 @@ pub fn fill_only_one_possible_hint_region(rule: &Rule, mut col_or_row: &mut Vec<
         let good_region = *good_regions[0];
         let skip = good_region.1 - hint;
         for i in skip..good_region.1 - skip {
-            *col_or_row[good_region.0 + i] = Cell::On;
+            col_or_row[good_region.0 + i] = Cell::On;
         }
 
         // fill holes in region
         let mut region_indices = good_region.0..good_region.0 + good_region.1;
-        let first_on_cell = region_indices.find(|&i| col_or_row[i] == &Cell::On);
-        let last_on_cell = region_indices.rfind(|&i| col_or_row[i] == &Cell::On);
+        let first_on_cell = region_indices.find(|&i| col_or_row[i] == Cell::On);
+        let last_on_cell = region_indices.rfind(|&i| col_or_row[i] == Cell::On);
         if let (Some(fst), Some(lst)) = (first_on_cell, last_on_cell) {
             if hint >= lst - fst {
                 for i in fst..=lst {
-                    *col_or_row[i] = Cell::On;
+                    col_or_row[i] = Cell::On;
                 }
             }
         }
     }
 }
 
-pub fn trim_finished_hints(
-    hints: &Vec<usize>, row: &mut Vec<&mut Cell>,
+pub fn trim_finished_hints<L: Line + ?Sized>(
+    hints: &[usize], row: &mut L,
 ) -> ((i32, i32), Vec<usize>) {
-    let mut hints = hints.clone();
+    let mut hints = hints.to_vec();
     let (mut start_pos, mut end_pos) = (0, row.len() as i32 - 1);
 
     // find zone starting position
     let mut i = 0_i32;
     while i < row.len() as i32 {
-        if *row[i as usize] == Cell::Off {
+        if row[i as usize] == Cell::Off {
             start_pos = i + 1;
             i += 1;
-        } else if *row[i as usize] == Cell::On {
+        } else if row[i as usize] == Cell::On {
             let block_length = Board::count_block_length(row, i as usize, false);
-            if block_length == hints[0] {
+            if hints.first() == Some(&block_length) {
                 hints.remove(0);
                 if hints.is_empty() {
                     break;
                 }
 
                 i += block_length as i32;
-                *row[i as usize] = Cell::Off;
+                if i < row.len() as i32 {
+                    row[i as usize] = Cell::Off;
+                }
                 start_pos = i + 1;
             }
             i += 1;
@@ -180,16 +330,16 @@ pub fn trim_finished_hints(
             break;
         }
 
-        if *row[i as usize] == Cell::Off {
+        if row[i as usize] == Cell::Off {
             end_pos = i - 1;
             i -= 1;
-        } else if *row[i as usize] == Cell::On {
+        } else if row[i as usize] == Cell::On {
             let block_length = Board::count_block_length(row, i as usize, true);
             if block_length == hints[hints.len() - 1] {
                 hints.pop();
                 i -= block_length as i32;
                 if i >= 0 {
-                    *row[i as usize] = Cell::Off;
+                    row[i as usize] = Cell::Off;
                     end_pos = i - 1;
                 }
             }
@@ -213,72 +363,19 @@ pub fn trim_finished_hints(
     ((start_pos, end_pos), hints)
 }
 
-pub fn block_intersection(rule: &Rule, row: &mut Vec<&mut Cell>) {
-    let mut hints = rule.hints.clone();
-    let (mut start_pos, mut end_pos) = (0, row.len() - 1);
-
-    println!("Row: {:?}", row);
-    let mut i = 0;
-    while i < row.len() {
-        if *row[i] == Cell::Off {
-            start_pos = i + 1;
-            i += 1;
-        } else if *row[i] == Cell::On {
-            let block_length = Board::count_block_length(row, i, false);
-            if block_length == *hints.first().unwrap() {
-                hints.remove(0);
-                i += block_length;
-                if i < row.len() {
-                    *row[i] = Cell::Off;
-                    start_pos = i + 1;
-                }
-            }
-            i += 1;
-        } else {
-            break;
-        }
-    }
-    if i >= row.len() {
-        return;
-    }
-
-    let mut i = row.len() - 1;
-    while i >= 0 {
-        if *row[i] == Cell::Off {
-            end_pos = i - 1;
-            i -= 1;
-        } else if *row[i] == Cell::On {
-            let block_length = Board::count_block_length(row, i, true);
-            if hints.is_empty() {
-                return;
-            }
-
-            if block_length == *hints.last().unwrap() {
-                hints.pop();
-                i -= block_length;
-                if i >= 0 {
-                    *row[i] = Cell::Off;
-                    end_pos = i - 1;
-                }
-            }
-            i -= 1;
-        } else {
-            break;
-        }
-    }
-
-    println!("Start pos: {}; End pos: {}", start_pos, end_pos);
+pub fn block_intersection<L: Line + ?Sized>(clue: &Clue, row: &mut L) {
+    let ((start_pos, end_pos), hints) = trim_finished_hints(&clue.hints, row);
 
     // find forward and backward blocks with hints
     let mut forward_blocks = vec![];
-    let mut pos = start_pos as i32;
+    let mut pos = start_pos;
     for hint in hints.iter() {
         forward_blocks.push((pos, pos + *hint as i32 - 1));
         pos += *hint as i32 + 1;
     }
 
     let mut reverse_blocks = vec![];
-    let mut pos = end_pos as i32;
+    let mut pos = end_pos;
     for hint in hints.iter().rev() {
         reverse_blocks.insert(0, (pos - *hint as i32 + 1, pos));
         pos -= *hint as i32 + 1;
@@ -298,20 +395,22 @@ pub fn block_intersection(rule: &Rule, row: &mut Vec<&mut Cell>) {
     // mark intersections
     for (i, &(start, end)) in block_intersections.iter().enumerate() {
         for j in start as usize..=end as usize {
-            *row[j] = Cell::On;
+            row[j] = Cell::On;
         }
         // if intersection is perfect, surround block with crosses
         if (end + 1 - start) == hints[i] as i32 {
-            row.get_mut((start - 1) as usize)
-                .and_then(|cell| Some(**cell = Cell::Off));
-            row.get_mut((end + 1) as usize)
-                .and_then(|cell| Some(**cell = Cell::Off));
+            if let Some(cell) = row.get_mut((start - 1) as usize) {
+                *cell = Cell::Off;
+            }
+            if let Some(cell) = row.get_mut((end + 1) as usize) {
+                *cell = Cell::Off;
+            }
         }
     }
 }
 
-pub fn simple_boxes(hints: &Vec<usize>, row: &mut Vec<&mut Cell>) {
-    let ((start, end), hints) = trim_finished_hints(&hints, row);
+pub fn simple_boxes<L: Line + ?Sized>(hints: &[usize], row: &mut L) {
+    let ((start, end), hints) = trim_finished_hints(hints, row);
 
     // find forward and backward blocks with hints
     let mut forward_blocks = vec![];
@@ -342,31 +441,42 @@ pub fn simple_boxes(hints: &Vec<usize>, row: &mut Vec<&mut Cell>) {
     // mark intersections
     for &(start, end) in block_intersections.iter() {
         for i in start as usize..=end as usize {
-            *row[i] = Cell::On;
+            row[i] = Cell::On;
         }
     }
 }
 
-pub fn fill_first_and_last_block_if_possible(hints: &Vec<usize>, row: &mut Vec<&mut Cell>) {
-    let ((start, end), hints) = trim_finished_hints(&hints, row);
+pub fn fill_first_and_last_block_if_possible<L: Line + ?Sized>(hints: &[usize], row: &mut L) {
+    let ((start, end), hints) = trim_finished_hints(hints, row);
     if hints.is_empty() {
         return;
     }
 
+    if start > end {
+        return;
+    }
     let (fst_index, lst_index) = (start as usize, end as usize);
-    let (fst_cell, lst_cell): (Cell, Cell) = (row[fst_index].clone(), row[lst_index].clone());
+    let (first_hint, last_hint) = (hints[0], hints[hints.len() - 1]);
+    // the first or last block doesn't fit, the line contradicts the hints
+    if fst_index + first_hint > row.len() || last_hint > lst_index + 1 {
+        return;
+    }
+
+    let (fst_cell, lst_cell): (Cell, Cell) = (row[fst_index], row[lst_index]);
     if let Cell::On = fst_cell {
-        for i in 0..hints[0] {
-            *row[fst_index + i] = Cell::On;
+        for i in 0..first_hint {
+            row[fst_index + i] = Cell::On;
+        }
+        if let Some(cell) = row.get_mut(fst_index + first_hint) {
+            *cell = Cell::Off;
         }
-        row.get_mut(fst_index + hints[0])
-            .and_then(|cell| Some(**cell = Cell::Off));
     }
     if let Cell::On = lst_cell {
-        for i in 0..hints[hints.len() - 1] {
-            *row[lst_index - i] = Cell::On;
+        for i in 0..last_hint {
+            row[lst_index - i] = Cell::On;
+        }
+        if let Some(before_block) = lst_index.checked_sub(last_hint) {
+            row[before_block] = Cell::Off;
         }
-        row.get_mut(lst_index - hints[hints.len() - 1])
-            .and_then(|cell| Some(**cell = Cell::Off));
     }
 }
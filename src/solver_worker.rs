@@ -0,0 +1,67 @@
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::thread;
+
+use nonogram_solver::board::Board;
+use nonogram_solver::solver;
+
+// Solves boards on a background thread so the frame loop never blocks on the solver.
+// Only the most recent request matters: older queued boards are dropped by the worker
+// and results for stale requests are ignored.
+pub struct SolverWorker {
+    requests: Sender<(u64, Board)>,
+    results: Receiver<(u64, bool)>,
+    last_requested: u64,
+    last_received: u64,
+}
+
+impl SolverWorker {
+    pub fn new() -> Self {
+        let (request_tx, request_rx) = channel::<(u64, Board)>();
+        let (result_tx, result_rx) = channel();
+
+        thread::spawn(move || {
+            while let Ok(mut request) = request_rx.recv() {
+                // skip everything but the latest board
+                while let Ok(newer_request) = request_rx.try_recv() {
+                    request = newer_request;
+                }
+
+                let (id, mut board) = request;
+                let is_solvable = solver::solve(&mut board, true).is_solved();
+                if result_tx.send((id, is_solvable)).is_err() {
+                    break;
+                }
+            }
+        });
+
+        SolverWorker {
+            requests: request_tx,
+            results: result_rx,
+            last_requested: 0,
+            last_received: 0,
+        }
+    }
+
+    pub fn request(&mut self, board: Board) {
+        self.last_requested += 1;
+        self.requests
+            .send((self.last_requested, board))
+            .expect("solver worker has stopped");
+    }
+
+    // returns the result of the latest request once it is ready
+    pub fn poll(&mut self) -> Option<bool> {
+        let mut latest = None;
+        while let Ok((id, is_solvable)) = self.results.try_recv() {
+            if id == self.last_requested {
+                latest = Some(is_solvable);
+            }
+            self.last_received = id;
+        }
+        latest
+    }
+
+    pub fn is_busy(&self) -> bool {
+        self.last_received != self.last_requested
+    }
+}
@@ -1,9 +1,21 @@
 
 #[cfg(test)]
 mod tests {
+    use std::fs;
+    use std::path::Path;
+    use std::time::{Duration, UNIX_EPOCH};
+
     use itertools::Itertools;
 
-    use crate::board::{Board, Cell, Rule};
+    use crate::analysis::{self, Difficulty, LineStatus, Uniqueness};
+    use crate::board::{Board, Cell, Clue, ClueErrorKind, LineId, Rule};
+    use crate::export::{self, ExportMode, PageOptions};
+    use crate::json::SolveReport;
+    use crate::library::{Library, PuzzleFile, PuzzleInfo};
+    use crate::line::Line;
+    use crate::progress::{Progress, ProgressError};
+    use crate::render::{self, RenderOptions};
+    use crate::solver::{self, SolveStatus};
 
     fn row_to_str(row: &Vec<Cell>) -> String {
         row.iter().map(|c| c.as_char()).collect()
@@ -16,10 +28,8 @@ mod tests {
             input.chars().map(Cell::from_char).collect::<Vec<_>>()
         };
 
-        let rule = Rule::new(0, false, hints);
-        let mut ref_row = row.iter_mut().map(|c| c).collect::<Vec<_>>();
-
-        Board::block_intersection(&rule, &mut ref_row);
+        let clue = Clue::new(hints);
+        solver::block_intersection(&clue, &mut row[..]);
 
         let correct_row = correct_input
             .chars()
@@ -35,9 +45,7 @@ mod tests {
             input.chars().map(Cell::from_char).collect::<Vec<_>>()
         };
 
-        let mut ref_row = row.iter_mut().map(|c| c).collect::<Vec<_>>();
-
-        Board::simple_boxes(&hints, &mut ref_row);
+        solver::simple_boxes(&hints, &mut row[..]);
 
         let correct_row = correct_input
             .chars()
@@ -46,7 +54,7 @@ mod tests {
         assert_eq!(row, correct_row);
     }
 
-    // #[test]
+    #[test]
     fn simple_block_intersection() {
         test_block_intersection(8, vec![4, 3], "        ", "XXXX.XXX");
         test_block_intersection(10, vec![8], "", "  XXXXXX  ");
@@ -66,7 +74,7 @@ mod tests {
         // test_block_intersection(8, vec![3, 3], " XXX XXX", ".XXX.XXX");
     }
 
-    // #[test]
+    #[test]
     fn simple_boxes() {
         test_simple_boxes(8, vec![4, 3], "        ", "XXXX XXX");
         test_simple_boxes(10, vec![8], "", "  XXXXXX  ");
@@ -75,26 +83,24 @@ mod tests {
         test_simple_boxes(10, vec![4, 3], "        ..", "XXXX XXX..");
     }
 
-    // #[test]
+    #[test]
     fn find_first_cell_such_that() {
         let input = ".. X";
 
-        let mut row = input.chars().map(Cell::from_char).collect::<Vec<_>>();
-
-        let mut ref_row = row.iter_mut().map(|c| c).collect::<Vec<_>>();
+        let row = input.chars().map(Cell::from_char).collect::<Vec<_>>();
 
         let fst_index = Board::find_first_cell_such_that(
-            &mut ref_row,
+            &row[..],
             |cell| matches!(cell, Cell::On | Cell::None),
             false,
         );
         let lst_index = Board::find_first_cell_such_that(
-            &mut ref_row,
+            &row[..],
             |cell| matches!(cell, Cell::On | Cell::None),
             true,
         );
         let lst_non_on = Board::find_first_cell_such_that(
-            &mut ref_row,
+            &row[..],
             |cell| matches!(cell, Cell::On | Cell::None),
             true,
         );
@@ -104,25 +110,21 @@ mod tests {
         assert_eq!(lst_non_on, Some(3));
     }
 
-    // #[test]
+    #[test]
     fn fill_first_and_last_block_if_possible() {
         let mut row = ".X X  .XX X"
             .chars()
             .map(Cell::from_char)
             .collect::<Vec<_>>();
-        let mut ref_row = row.iter_mut().map(|c| c).collect::<Vec<_>>();
-
-        Board::fill_first_and_last_block_if_possible(&vec![1, 3, 4], &mut ref_row);
+        solver::fill_first_and_last_block_if_possible(&[1, 3, 4], &mut row[..]);
 
         assert_eq!(row_to_str(&row), ".X.XXX.XXXX");
     }
 
-    // #[test]
+    #[test]
     fn trim_finished_hints() {
         let mut row = ".XX..X XX.. X".chars().map(Cell::from_char).collect_vec();
-        let mut ref_row = row.iter_mut().map(|c| c).collect_vec();
-
-        let ((start, end), hints) = Board::trim_finished_hints(&vec![2, 4, 2], &mut ref_row);
+        let ((start, end), hints) = solver::trim_finished_hints(&[2, 4, 2], &mut row[..]);
 
         // assert!(hints.is_empty());
         assert_eq!(hints, vec![4, 2]);
@@ -131,14 +133,727 @@ mod tests {
 
     #[test]
     fn get_consecutive_regions() {
-        let mut row = ".XX..X XX.. X".chars().map(Cell::from_char).collect_vec();
+        let row = ".XX..X XX.. X".chars().map(Cell::from_char).collect_vec();
         let len = row.len();
 
-        let mut ref_row = row.iter_mut().map(|c| c).collect_vec();
-
-        let regions = Board::get_consecutive_regions(&mut ref_row, true, Some((3, len - 1)));
+        let regions = Board::get_consecutive_regions(&row[..], true, Some((3, len - 1)));
 
         // assert!(hints.is_empty());
         assert_eq!(regions, vec![(5, 4), (11, 2)]);
     }
+
+    // inputs the fuzz targets crashed on
+    #[test]
+    fn fuzz_regressions() {
+        let line = |s: &str| s.chars().map(Cell::from_char).collect_vec();
+
+        assert!(Board::get_consecutive_regions(&line("")[..], true, None).is_empty());
+        assert_eq!(Board::count_block_length(&line("XX.")[..], 1, true), 2);
+        assert_eq!(Board::count_block_length(&line("X")[..], 5, true), 0);
+
+        let mut row = line("X ");
+        solver::trim_finished_hints(&[], &mut row[..]);
+        let mut row = line("XX");
+        solver::trim_finished_hints(&[2, 1], &mut row[..]);
+        assert_eq!(row_to_str(&row), "XX");
+
+        let mut row = line("X...");
+        solver::fill_first_and_last_block_if_possible(&[3], &mut row[..]);
+        assert_eq!(row_to_str(&row), "X...");
+        let mut row = line("....");
+        solver::cross_impossible_region(&[1], &mut row[..]);
+        assert_eq!(row_to_str(&row), "....");
+
+        for input in &["", "X", "X.X", " X.", "XXXX"] {
+            for hints in &[vec![], vec![1], vec![5], vec![1, 1, 1]] {
+                solver::block_intersection(&Clue::new(hints.clone()), &mut line(input)[..]);
+            }
+        }
+
+        let mut board: Board = serde_json::from_str(r#"{"rows":2,"cols":0,"row_clues":[[],[]],"col_clues":[]}"#).unwrap();
+        solver::solve(&mut board, false);
+    }
+
+    #[test]
+    fn line_rules_on_rows_cols_and_arrays() {
+        let mut cells = [Cell::None; 5];
+        solver::simple_boxes(&[4], &mut cells[..]);
+        assert_eq!(row_to_str(&cells.to_vec()), " XXX ");
+
+        let mut board = Board::new(5, 2);
+        solver::simple_boxes(&[4], &mut board.get_col(1));
+        solver::simple_boxes(&[2], board.get_row(4));
+        let col = board.get_col(1).cells();
+        assert_eq!(row_to_str(&col), " XXXX");
+        assert_eq!(board.get_line(LineId::Row(4)).len(), 2);
+        assert_eq!(board.get_line(LineId::Col(1)).len(), 5);
+    }
+
+    #[test]
+    fn board_json_round_trip() {
+        let mut board = Board::with_clues(
+            vec![vec![1, 1].into(), vec![3].into()],
+            vec![vec![2].into(), vec![1].into(), vec![2].into()],
+        );
+        board.data[0] = "X. ".chars().map(Cell::from_char).collect();
+
+        let json = serde_json::to_string(&board).unwrap();
+        assert_eq!(
+            json,
+            r#"{"rows":2,"cols":3,"row_clues":[[1,1],[3]],"col_clues":[[2],[1],[2]],"grid":["X. ","   "]}"#
+        );
+
+        let parsed: Board = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.data, board.data);
+        assert_eq!(serde_json::to_string(&parsed).unwrap(), json);
+    }
+
+    #[test]
+    fn board_json_without_grid_is_blank() {
+        let json = r#"{"rows":1,"cols":2,"row_clues":[[2]],"col_clues":[[1],[1]]}"#;
+        let board: Board = serde_json::from_str(json).unwrap();
+
+        assert_eq!(board.data, vec![vec![Cell::None; 2]]);
+        assert_eq!(board.row_clues(), &[Clue::new(vec![2])]);
+        assert_eq!(board.clue(LineId::Col(1)).hints, vec![1]);
+    }
+
+    #[test]
+    fn board_json_rejects_malformed_input() {
+        let missing_clue = r#"{"rows":2,"cols":1,"row_clues":[[1]],"col_clues":[[1]]}"#;
+        let err = serde_json::from_str::<Board>(missing_clue).unwrap_err();
+        assert!(err.to_string().contains("row_clues has 1 entries, expected 2"));
+
+        let bad_cell = r##"{"rows":1,"cols":1,"row_clues":[[1]],"col_clues":[[1]],"grid":["#"]}"##;
+        let err = serde_json::from_str::<Board>(bad_cell).unwrap_err();
+        assert!(err.to_string().contains("unknown cell '#'"));
+
+        let short_row = r#"{"rows":1,"cols":2,"row_clues":[[1]],"col_clues":[[1],[]],"grid":["X"]}"#;
+        assert!(serde_json::from_str::<Board>(short_row).is_err());
+    }
+
+    #[test]
+    fn cell_and_rule_json() {
+        assert_eq!(serde_json::to_string(&Cell::On).unwrap(), r#""X""#);
+        assert_eq!(serde_json::from_str::<Cell>(r#"".""#).unwrap(), Cell::Off);
+        assert!(serde_json::from_str::<Cell>(r#""?""#).is_err());
+
+        let rule = Rule::new(3, true, vec![1, 2]);
+        assert_eq!(
+            serde_json::to_string(&rule).unwrap(),
+            r#"{"n":3,"is_col":true,"hints":[1,2]}"#
+        );
+    }
+
+    #[test]
+    fn solve_report_json() {
+        let json = r#"{"rows":2,"cols":2,"row_clues":[[2],[1]],"col_clues":[[2],[1]]}"#;
+        let mut board: Board = serde_json::from_str(json).unwrap();
+        let outcome = solver::solve(&mut board, true);
+
+        let report = serde_json::to_value(SolveReport::new(outcome, board)).unwrap();
+        assert_eq!(report["status"], "solved");
+        assert_eq!(report["stats"]["cells_solved"], 4);
+        assert_eq!(report["board"]["grid"], serde_json::json!(["XX", "X."]));
+    }
+
+    #[test]
+    fn validate_clues() {
+        let mut board = Board::new(2, 2);
+        board.set_clue(LineId::Row(0), Clue::new(vec![2, 2]));
+        board.set_clue(LineId::Col(0), Clue::new(vec![0]));
+        let errors = board
+            .validate()
+            .into_iter()
+            .map(|err| (err.line, err.kind))
+            .collect_vec();
+        assert_eq!(
+            errors,
+            vec![
+                (Some(LineId::Row(0)), ClueErrorKind::HintsTooLong),
+                (Some(LineId::Col(0)), ClueErrorKind::ZeroHint),
+            ]
+        );
+
+        let totals = Board::with_clues(vec![vec![2].into()], vec![vec![1].into(), vec![].into()]);
+        let errors = totals.validate();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].kind, ClueErrorKind::TotalsMismatch);
+        assert!(crate::puzzles::run_nonogram_solver().validate().is_empty());
+    }
+
+    #[test]
+    fn board_from_rules() {
+        let rules = vec![
+            Rule::new(0, false, vec![1]),
+            Rule::new(0, false, vec![1]),
+            Rule::new(0, true, vec![1]),
+            Rule::new(2, true, vec![1]),
+        ];
+        let errors = Board::from_rules(2, 2, rules)
+            .unwrap_err()
+            .into_iter()
+            .map(|err| (err.line, err.kind))
+            .collect_vec();
+        assert_eq!(
+            errors,
+            vec![
+                (Some(LineId::Col(2)), ClueErrorKind::LineOutOfRange),
+                (Some(LineId::Row(0)), ClueErrorKind::DuplicateLine),
+                (Some(LineId::Row(1)), ClueErrorKind::MissingLine),
+                (Some(LineId::Col(1)), ClueErrorKind::MissingLine),
+            ]
+        );
+
+        let rules = vec![
+            Rule::new(1, true, vec![]),
+            Rule::new(0, false, vec![1]),
+            Rule::new(0, true, vec![1]),
+        ];
+        let board = Board::from_rules(1, 2, rules).unwrap();
+        assert_eq!(board.col_clues(), &[Clue::new(vec![1]), Clue::new(vec![])]);
+        let lines = board.rules().iter().map(|r| (r.n, r.is_col)).collect_vec();
+        assert_eq!(lines, vec![(0, false), (0, true), (1, true)]);
+    }
+
+    #[test]
+    fn invalid_clues_are_rejected() {
+        let json = r#"{"rows":1,"cols":2,"row_clues":[[3]],"col_clues":[[1],[1]]}"#;
+        let err = serde_json::from_str::<Board>(json).unwrap_err();
+        assert!(err.to_string().contains("row 0: hints [3] need 3 cells but the line has 2"));
+
+        let mut board = Board::with_clues(vec![vec![3].into()], vec![vec![1].into(); 2]);
+        board.data[0][0] = Cell::On;
+        let outcome = solver::solve(&mut board, true);
+        assert_eq!(outcome.status, SolveStatus::InvalidClues);
+        assert_eq!(board.data[0][0], Cell::On);
+        assert_eq!(analysis::check_uniqueness(&board), Uniqueness::NoSolution);
+    }
+
+    fn board_from_json(json: &str) -> Board {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn uniqueness() {
+        let unique = board_from_json(r#"{"rows":2,"cols":2,"row_clues":[[2],[1]],"col_clues":[[2],[1]]}"#);
+        let diagonal = board_from_json(r#"{"rows":2,"cols":2,"row_clues":[[1],[1]],"col_clues":[[1],[1]]}"#);
+        let broken = board_from_json(r#"{"rows":2,"cols":2,"row_clues":[[2],[]],"col_clues":[[],[2]]}"#);
+
+        assert_eq!(analysis::check_uniqueness(&unique), Uniqueness::Unique);
+        assert_eq!(analysis::check_uniqueness(&diagonal), Uniqueness::Multiple);
+        assert_eq!(analysis::check_uniqueness(&broken), Uniqueness::NoSolution);
+
+        let solution = analysis::find_unique_solution(&unique).unwrap();
+        assert_eq!(solution.data[1], vec![Cell::On, Cell::Off]);
+        assert!(analysis::find_unique_solution(&diagonal).is_none());
+
+        assert_eq!(analysis::check_uniqueness_within(&unique, 1), Ok(Uniqueness::Unique));
+        assert_eq!(analysis::check_uniqueness_within(&diagonal, 3), Ok(Uniqueness::Multiple));
+        assert_eq!(analysis::check_uniqueness_within(&diagonal, 2), Err(analysis::OutOfBudget));
+        assert_eq!(analysis::grade_difficulty_within(&diagonal, 0), Err(analysis::OutOfBudget));
+    }
+
+    #[test]
+    fn difficulty() {
+        let easy = board_from_json(r#"{"rows":2,"cols":2,"row_clues":[[2],[1]],"col_clues":[[2],[1]]}"#);
+        let diagonal = board_from_json(r#"{"rows":2,"cols":2,"row_clues":[[1],[1]],"col_clues":[[1],[1]]}"#);
+
+        assert_eq!(analysis::grade_difficulty(&easy), Some(Difficulty::Easy));
+        assert_eq!(analysis::grade_difficulty(&diagonal), None);
+    }
+
+    #[test]
+    fn reference_and_hard_puzzles() {
+        for (name, board) in crate::puzzles::reference_puzzles() {
+            assert_eq!(analysis::grade_difficulty(&board), Some(Difficulty::Easy), "{}", name);
+        }
+        for (name, board) in crate::puzzles::hard_puzzles() {
+            assert_eq!(analysis::grade_difficulty(&board), Some(Difficulty::Hard), "{}", name);
+        }
+    }
+
+    #[test]
+    fn puzzle_library() {
+        let mut library = Library::load(concat!(env!("CARGO_MANIFEST_DIR"), "/puzzles")).unwrap();
+        assert!(library.errors.is_empty());
+        assert!(library.puzzles.iter().all(|entry| !entry.graded));
+        library.grade_all();
+
+        let puzzles = crate::puzzles::reference_puzzles()
+            .into_iter()
+            .chain(crate::puzzles::hard_puzzles())
+            .collect_vec();
+        assert_eq!(library.puzzles.len(), puzzles.len());
+        for (name, board) in puzzles {
+            let entry = library.get(&name.replace(' ', "-")).unwrap();
+            assert_eq!(entry.board.row_clues(), board.row_clues(), "{}", name);
+            assert_eq!(entry.board.col_clues(), board.col_clues(), "{}", name);
+            assert!(entry.solution.as_ref().unwrap().satisfies_clues());
+            assert_eq!(entry.difficulty, analysis::grade_difficulty(&board));
+        }
+    }
+
+    #[test]
+    fn library_remembers_completed_puzzles() {
+        let dir = std::env::temp_dir().join(format!("nonogram_library_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let board = r#"{"rows":1,"cols":3,"row_clues":[[1,1]],"col_clues":[[1],[],[1]]}"#;
+        fs::write(dir.join("dots.json"), board).unwrap();
+        fs::write(dir.join("broken.json"), "{").unwrap();
+        fs::write(dir.join("notes.txt"), "not a puzzle").unwrap();
+
+        let mut library = Library::load(&dir).unwrap();
+        assert_eq!(library.puzzles.iter().map(|e| e.name.as_str()).collect_vec(), ["dots"]);
+        assert_eq!(library.errors.len(), 1);
+        assert!(!library.is_completed("dots"));
+        library.mark_completed("dots").unwrap();
+
+        let library = Library::load(&dir).unwrap();
+        assert!(library.is_completed("dots"));
+        assert_eq!(library.puzzles.len(), 1);
+
+        // a broken list of completed puzzles doesn't hide the puzzles
+        fs::write(dir.join("completed.json"), "[").unwrap();
+        let mut library = Library::load(&dir).unwrap();
+        assert!(!library.is_completed("dots"));
+        assert_eq!(library.puzzles.len(), 1);
+        assert_eq!(library.errors.len(), 2);
+        // nor is it written over
+        assert!(library.mark_completed("dots").is_err());
+        assert_eq!(fs::read_to_string(dir.join("completed.json")).unwrap(), "[");
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert!(Library::load(&dir).unwrap().puzzles.is_empty());
+    }
+
+    #[test]
+    fn library_best_times_and_next_puzzle() {
+        let dir = std::env::temp_dir().join(format!("nonogram_best_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let board = r#"{"rows":1,"cols":1,"row_clues":[[1]],"col_clues":[[1]]}"#;
+        for name in ["a", "b", "c"].iter() {
+            fs::write(dir.join(format!("{}.json", name)), board).unwrap();
+        }
+
+        let mut library = Library::load(&dir).unwrap();
+        assert_eq!(library.best_time("a"), None);
+        assert!(library.record_time("a", 90.0).unwrap());
+        assert!(!library.record_time("a", 95.0).unwrap());
+        assert!(library.record_time("a", 60.5).unwrap());
+        assert_eq!(library.best_time("a"), Some(60.5));
+
+        let next = |library: &Library, name| library.next_puzzle(name).map(|e| e.name.clone());
+        assert_eq!(next(&library, "a").as_deref(), Some("b"));
+        library.mark_completed("b").unwrap();
+        assert_eq!(next(&library, "a").as_deref(), Some("c"));
+        library.mark_completed("c").unwrap();
+        assert_eq!(next(&library, "b").as_deref(), Some("a"));
+        library.mark_completed("a").unwrap();
+        assert_eq!(next(&library, "c").as_deref(), Some("a"));
+        assert_eq!(next(&library, "missing"), None);
+
+        // best_times.json isn't taken for a puzzle
+        let library = Library::load(&dir).unwrap();
+        assert_eq!(library.puzzles.len(), 3);
+        assert!(library.errors.is_empty());
+        assert_eq!(library.best_time("a"), Some(60.5));
+        assert_eq!(library.best_time("b"), None);
+
+        fs::write(dir.join("best_times.json"), "{\"a\":").unwrap();
+        let mut library = Library::load(&dir).unwrap();
+        assert_eq!(library.puzzles.len(), 3);
+        assert_eq!(library.errors.len(), 1);
+        assert_eq!(library.best_time("a"), None);
+        assert!(library.record_time("a", 90.0).is_err());
+        assert_eq!(fs::read_to_string(dir.join("best_times.json")).unwrap(), "{\"a\":");
+        fs::remove_dir_all(&dir).unwrap();
+
+        let single = Library::new(&dir);
+        assert!(single.next_puzzle("a").is_none());
+    }
+
+    #[test]
+    fn save_created_puzzles() {
+        let dir = std::env::temp_dir().join(format!("nonogram_created_{}", std::process::id()));
+        let mut library = Library::new(&dir);
+        let mut picture = Board::new(2, 3);
+        picture.data = vec![
+            vec![Cell::On, Cell::None, Cell::On],
+            vec![Cell::On, Cell::On, Cell::On],
+        ];
+
+        let file = PuzzleFile::new(&picture, " My House ", "");
+        assert_eq!(file.info.title.as_deref(), Some("My House"));
+        assert_eq!(file.info.author, None);
+        assert_eq!(file.info.uniqueness, Some(Uniqueness::Unique));
+        assert_eq!(file.info.difficulty, Some(Difficulty::Easy));
+        assert_eq!(file.board.row_clues(), &[vec![1, 1].into(), vec![3].into()]);
+        let mut diagonal = Board::new(2, 2);
+        diagonal.data = vec![vec![Cell::On, Cell::Off], vec![Cell::Off, Cell::On]];
+        let ambiguous = PuzzleFile::new(&diagonal, "", "");
+        assert_eq!(ambiguous.info.uniqueness, Some(Uniqueness::Multiple));
+        assert_eq!(ambiguous.info.difficulty, None);
+
+        let name = library.name_for("My House");
+        assert_eq!(name, "my-house");
+        library.save_puzzle(&name, file).unwrap();
+        assert_eq!(library.name_for("my house"), "my-house-2");
+        assert_eq!(library.name_for("?"), "puzzle");
+
+        let library = Library::load(&dir).unwrap();
+        let entry = library.get("my-house").unwrap();
+        assert_eq!(entry.title(), "My House");
+        assert_eq!(entry.info.difficulty, Some(Difficulty::Easy));
+        assert!(entry.info.date.is_some());
+        assert_eq!(entry.picture.as_ref().unwrap().data, picture.data);
+        assert!(!entry.board.data.iter().flatten().any(|&c| c != Cell::None));
+        assert!(entry.graded);
+        let solution = entry.solution.as_ref().unwrap();
+        assert_eq!(solution.data[0], vec![Cell::On, Cell::Off, Cell::On]);
+        assert_eq!(solution.data[1], picture.data[1]);
+        fs::remove_dir_all(&dir).unwrap();
+
+        let plain = concat!(env!("CARGO_MANIFEST_DIR"), "/puzzles/8x8.json");
+        assert_eq!(PuzzleFile::load(Path::new(plain)).unwrap().info, PuzzleInfo::default());
+
+        let day = Duration::from_secs(1_700_000_000);
+        assert_eq!(crate::library::date_of(UNIX_EPOCH + day), "2023-11-14");
+        assert_eq!(crate::library::date_of(UNIX_EPOCH), "1970-01-01");
+    }
+
+    #[test]
+    fn progress_round_trip() {
+        let dir = std::env::temp_dir().join(format!("nonogram_progress_{}", std::process::id()));
+        let mut library = Library::new(&dir);
+        let path = library.progress_path("dots");
+        assert_eq!(Progress::load(&path).unwrap(), None);
+
+        let mut board = Board::with_clues(vec![vec![1, 1].into()], vec![vec![1].into(); 3]);
+        board.data[0] = vec![Cell::On, Cell::Off, Cell::None];
+        let saved = Progress {
+            moves: 7,
+            hints: 1,
+            ..Progress::new(&board, 12.5, 2)
+        };
+        library.save_progress("dots", &saved).unwrap();
+        assert!(library.has_progress("dots"));
+        assert!(Library::load(&dir).unwrap().has_progress("dots"));
+
+        let progress = Progress::load(&path).unwrap().unwrap();
+        assert_eq!(progress, saved);
+        let mut resumed = board.clone();
+        resumed.clear_board();
+        progress.restore(&mut resumed).unwrap();
+        assert_eq!(resumed.data, board.data);
+        assert!(progress.restore(&mut Board::new(2, 3)).is_err());
+
+        // saved before moves and hints were counted
+        let older = r#"{"version":1,"grid":["X. "],"elapsed_secs":3.0,"mistakes":0}"#;
+        fs::write(&path, older).unwrap();
+        let progress = Progress::load(&path).unwrap().unwrap();
+        assert_eq!((progress.moves, progress.hints), (0, 0));
+
+        let newer = r#"{"version":2,"cells":"X. "}"#;
+        fs::write(&path, newer).unwrap();
+        match Progress::load(&path) {
+            Err(ProgressError::UnsupportedVersion(2)) => {}
+            other => panic!("{:?}", other),
+        }
+
+        library.clear_progress("dots").unwrap();
+        library.clear_progress("dots").unwrap();
+        assert!(!library.has_progress("dots"));
+        assert!(!Library::load(&dir).unwrap().has_progress("dots"));
+        library.mark_completed("dots").unwrap();
+        assert!(Library::load(&dir).unwrap().puzzles.is_empty());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn satisfies_clues() {
+        let json = r#"{"rows":1,"cols":3,"row_clues":[[1]],"col_clues":[[1],[],[]]}"#;
+        let mut board = board_from_json(json);
+        assert!(!board.satisfies_clues());
+        board.data[0][2] = Cell::On;
+        assert!(!board.satisfies_clues());
+        board.data[0] = vec![Cell::On, Cell::Off, Cell::None];
+        assert!(board.satisfies_clues());
+    }
+
+    #[test]
+    fn format_board_guides() {
+        let json = r#"{"rows":3,"cols":3,"row_clues":[[1],[2],[]],"col_clues":[[2],[1],[]],
+            "grid":["X..","XX.","..."]}"#;
+        let board = board_from_json(json);
+        let lines = |lines: &[&str]| lines.iter().map(|l| l.to_string()).collect_vec();
+
+        assert_eq!(
+            board.format_board(2),
+            lines(&["  21  ", "  ____", "1|X.|.", "2|XX|.", " |--+-", " |..|."])
+        );
+        assert_eq!(
+            board.format_board(0),
+            lines(&["  21 ", "  ___", "1|X..", "2|XX.", " |..."])
+        );
+    }
+
+    #[test]
+    fn format_board_multi_digit_hints() {
+        let json = r#"{"rows":2,"cols":12,"row_clues":[[12],[1,10]],
+            "col_clues":[[2],[1],[2],[2],[2],[2],[2],[2],[2],[2],[2],[2]]}"#;
+        let board = board_from_json(json);
+        let lines = board.format_board(0);
+
+        assert_eq!(lines[0], format!("{}{}", " ".repeat(7), "212222222222"));
+        assert_eq!(lines[2], format!("{}|{}", " 12   ", " ".repeat(12)));
+        assert_eq!(lines[3], format!("{}|{}", "  1 10", " ".repeat(12)));
+    }
+
+    #[test]
+    fn export_booklet() {
+        let json = r#"{"rows":2,"cols":12,"row_clues":[[12],[1]],
+            "col_clues":[[2],[1],[1],[1],[1],[1],[1],[1],[1],[1],[1],[1]],
+            "grid":["XXXXXXXXXXXX","X..........."]}"#;
+        let board = board_from_json(json);
+        let puzzles = vec![("A & B", &board); 3];
+        let mut options = PageOptions {
+            title: Some("Booklet".to_owned()),
+            columns: 2,
+            ..PageOptions::default()
+        };
+
+        let pages = export::to_svg(&puzzles, &options);
+        assert_eq!(pages.len(), 2);
+        assert_eq!(pages[0].matches("A &amp; B").count(), 2);
+        assert_eq!(pages[1].matches("Booklet").count(), 1);
+        assert!(pages[0].contains(">12</text>"));
+        assert!(!pages[0].contains(r#"fill="black""#));
+
+        options.mode = ExportMode::Solution;
+        let pages = export::to_svg(&puzzles, &options);
+        assert_eq!(pages[0].matches(r#"fill="black""#).count(), 2 * 13);
+
+        let pdf = String::from_utf8(export::to_pdf(&puzzles, &options)).unwrap();
+        assert!(pdf.starts_with("%PDF-1.4"));
+        assert!(pdf.ends_with("%%EOF\n"));
+        assert!(pdf.contains("/Count 2"));
+        assert!(pdf.contains("(A & B) Tj"));
+
+        // WinAnsiEncoding, not only Latin-1
+        options.title = Some("Café — “Noël” €5 ☃".to_owned());
+        let pdf = export::to_pdf(&puzzles, &options);
+        let title = b"(Caf\xe9 \x97 \x93No\xebl\x94 \x805 ?) Tj";
+        assert!(pdf.windows(title.len()).any(|bytes| bytes == title));
+    }
+
+    #[test]
+    fn render_board_to_png() {
+        let json = r#"{"rows":2,"cols":3,"row_clues":[[1],[2]],"col_clues":[[2],[1],[]],
+            "grid":["X..","XX "]}"#;
+        let board = board_from_json(json);
+        let options = RenderOptions {
+            cell_size: 10,
+            ..RenderOptions::default()
+        };
+        let image = render::render_board(&board, &options);
+
+        // a cell of clues left of and above the grid
+        assert_eq!((image.width, image.height), (10 + 3 * 10 + 1, 10 + 2 * 10 + 1));
+        let center = |n_row: usize, n_col: usize| image.pixel(15 + n_col * 10, 15 + n_row * 10);
+        assert_eq!(center(0, 0), center(1, 1));
+        assert_ne!(center(0, 0), center(0, 1));
+        assert_ne!(center(0, 1), center(1, 2));
+        assert_eq!(center(1, 2), [255, 255, 255]);
+
+        let no_clues = RenderOptions {
+            clues: false,
+            ..options
+        };
+        let image = render::render_board(&board, &no_clues);
+        assert_eq!((image.width, image.height), (31, 21));
+
+        let mut png = vec![];
+        image.write_png(&mut png).unwrap();
+        assert!(png.starts_with(b"\x89PNG"));
+    }
+
+    #[test]
+    fn solve_playback() {
+        let mut board = crate::puzzles::run_nonogram_solver();
+        let (_, trace) = solver::solve_with_trace(&mut board.clone(), true);
+        let frames = render::render_trace(&board, &trace, &RenderOptions::default());
+
+        assert_eq!(frames.len(), trace.len() + 1);
+        assert!(frames.iter().all(|f| (f.width, f.height) == (frames[0].width, frames[0].height)));
+        board.clear_board();
+        let blank = render::render_board(&board, &RenderOptions::default());
+        assert_eq!(frames[0].width, blank.width);
+        assert!(frames[0].height > blank.height); // the caption
+
+        let mut gif = vec![];
+        render::write_gif(&frames[..3], 100, &mut gif).unwrap();
+        assert!(gif.starts_with(b"GIF89a"));
+    }
+
+    #[test]
+    fn line_status() {
+        let status = |hints: &[usize], line: &str| {
+            let row = line.chars().map(Cell::from_char).collect_vec();
+            analysis::line_status(hints, &row[..])
+        };
+        let unfinished = |done: &[bool]| LineStatus::Unfinished(done.to_vec());
+
+        assert_eq!(status(&[2, 1], "XX X "), LineStatus::Satisfied);
+        assert_eq!(status(&[2, 1], "XX.X."), LineStatus::Satisfied);
+        assert_eq!(status(&[], "  .  "), LineStatus::Satisfied);
+        assert_eq!(status(&[2, 1], "XXXX "), LineStatus::Contradiction);
+        assert_eq!(status(&[2, 1], "X.X.X"), LineStatus::Contradiction);
+        assert_eq!(status(&[1, 1], "X    "), unfinished(&[false, false]));
+
+        assert_eq!(status(&[2, 1, 1], "XX.      "), unfinished(&[true, false, false]));
+        assert_eq!(status(&[2, 1, 1], ".XX      "), unfinished(&[false, false, false]));
+        assert_eq!(status(&[2, 1, 1], "  XX     "), unfinished(&[false, false, false]));
+        assert_eq!(status(&[2, 1, 1], "..XX.X.  "), unfinished(&[true, true, false]));
+        assert_eq!(status(&[2, 1, 1], "    X .X."), unfinished(&[false, false, true]));
+        assert_eq!(status(&[2, 1, 1], "XX.   .X "), unfinished(&[true, false, false]));
+        assert_eq!(status(&[2, 1, 1], "XX.     .X"), unfinished(&[true, false, true]));
+    }
+
+    #[test]
+    fn likely_block() {
+        let likely = |hints: &[usize], line: &str, start: usize, length: usize| {
+            let row = line.chars().map(Cell::from_char).collect_vec();
+            analysis::likely_block(hints, &row[..], start, length)
+        };
+
+        assert_eq!(likely(&[1, 1], " X   ", 1, 1), Some(0));
+        assert_eq!(likely(&[1, 1], "   X  ", 3, 1), Some(1));
+        assert_eq!(likely(&[1, 3], "  X     ", 2, 1), Some(0));
+        assert_eq!(likely(&[1, 3], "   XX   ", 3, 2), Some(1));
+        assert_eq!(likely(&[3, 1], "X.    ", 0, 1), None);
+        assert_eq!(likely(&[2, 2], "  X  ", 2, 1), None);
+    }
+
+    #[test]
+    fn trace_covers_every_solved_cell() {
+        let mut board = crate::puzzles::run_nonogram_solver();
+        let (outcome, steps) = solver::solve_with_trace(&mut board, true);
+
+        let traced_cells = steps.iter().map(|step| step.cells.len()).sum::<usize>();
+        assert!(traced_cells >= outcome.stats.cells_solved);
+        for step in steps.iter() {
+            for cell in step.cells.iter() {
+                let n = if step.is_col { cell.col } else { cell.row };
+                assert_eq!(n, step.n);
+            }
+        }
+
+        let last_values = steps.iter().flat_map(|step| step.cells.iter()).fold(
+            vec![vec![Cell::None; board.cols]; board.rows],
+            |mut data, cell| {
+                data[cell.row][cell.col] = cell.cell;
+                data
+            },
+        );
+        assert_eq!(last_values, board.data);
+    }
+
+    #[cfg(feature = "server")]
+    mod server {
+        use std::io::{Read, Write};
+        use std::net::TcpStream;
+        use std::thread;
+
+        use tiny_http::Method;
+
+        use crate::server;
+
+        fn post(path: &str, body: &str) -> (u16, serde_json::Value) {
+            let (status, json) = server::handle(&Method::Post, path, body);
+            (status, serde_json::from_str(&json).unwrap())
+        }
+
+        #[test]
+        fn endpoints() {
+            let board = r#"{"rows":2,"cols":2,"row_clues":[[2],[1]],"col_clues":[[2],[1]]}"#;
+
+            let (status, json) = post("/solve", board);
+            assert_eq!(status, 200);
+            assert_eq!(json["status"], "solved");
+            assert_eq!(json["board"]["grid"], serde_json::json!(["XX", "X."]));
+
+            assert_eq!(post("/uniqueness", board).1["uniqueness"], "unique");
+            assert_eq!(post("/difficulty", board).1["difficulty"], "easy");
+
+            let (_, json) = post("/trace", board);
+            assert_eq!(json["status"], "solved");
+            assert_eq!(json["steps"][0]["technique"], "simple_boxes");
+
+            let (status, json) = post("/clues", r#"{"grid":["X.X","XXX"]}"#);
+            assert_eq!(status, 200);
+            assert_eq!(json["row_clues"], serde_json::json!([[1, 1], [3]]));
+            assert_eq!(json["col_clues"], serde_json::json!([[2], [1], [2]]));
+        }
+
+        #[test]
+        fn bad_requests() {
+            assert_eq!(post("/solve", "{").0, 400);
+            assert_eq!(post("/clues", r#"{"grid":["X?"]}"#).0, 400);
+            assert_eq!(post("/nothing", "{}").0, 404);
+            assert_eq!(server::handle(&Method::Get, "/solve", "").0, 405);
+
+            let size = 51;
+            let clues = vec![Vec::<usize>::new(); size];
+            let huge = serde_json::json!({
+                "rows": size, "cols": size, "row_clues": clues, "col_clues": clues
+            });
+            assert_eq!(post("/uniqueness", &huge.to_string()).0, 413);
+            assert_eq!(post("/difficulty", &huge.to_string()).0, 413);
+            assert_eq!(post("/solve", &huge.to_string()).0, 413);
+            assert_eq!(post("/trace", &huge.to_string()).0, 413);
+            let rows = vec!["X".repeat(size); size];
+            assert_eq!(post("/clues", &serde_json::json!({ "grid": rows }).to_string()).0, 413);
+            let endless = r#"{"rows":4000000000,"cols":4000000000,"row_clues":[],"col_clues":[]}"#;
+            assert_eq!(post("/solve", endless).0, 413);
+        }
+
+        #[test]
+        fn serves_localhost() {
+            let http_server = server::bind("127.0.0.1:0").unwrap();
+            let addr = http_server.server_addr().to_ip().unwrap();
+            thread::spawn(move || server::serve(&http_server));
+
+            let body = r#"{"rows":1,"cols":1,"row_clues":[[1]],"col_clues":[[1]]}"#;
+            let mut stream = TcpStream::connect(addr).unwrap();
+            write!(
+                stream,
+                "POST /solve HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\
+                 Content-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+                body.len(),
+                body
+            )
+            .unwrap();
+
+            let mut response = String::new();
+            stream.read_to_string(&mut response).unwrap();
+            assert!(response.starts_with("HTTP/1.1 200"));
+            assert!(response.contains(r#""grid":["X"]"#));
+
+            let body = " ".repeat(server::MAX_BODY_BYTES + 1);
+            let mut stream = TcpStream::connect(addr).unwrap();
+            write!(
+                stream,
+                "POST /solve HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\
+                 Content-Length: {}\r\n\r\n{}",
+                body.len(),
+                body
+            )
+            .unwrap();
+
+            let mut response = String::new();
+            stream.read_to_string(&mut response).unwrap();
+            assert!(response.starts_with("HTTP/1.1 413"));
+        }
+    }
 }
@@ -0,0 +1,36 @@
+// JavaScript bindings for the browser, built without the macroquad window:
+//   wasm-pack build --target web -- --no-default-features
+//
+//   const result = solve([[2], [1]], [[2], [1]]);
+//   result.status     // "solved"
+//   result.board.grid // ["XX", "X."]
+//
+// The result has the same shape as the solve report in `json.rs`.
+
+use std::convert::TryFrom;
+
+use wasm_bindgen::prelude::*;
+
+use crate::board::Board;
+use crate::json::{BoardJson, SolveReport};
+use crate::solver;
+
+#[wasm_bindgen]
+pub fn solve(row_clues: JsValue, col_clues: JsValue) -> Result<JsValue, JsValue> {
+    let row_clues: Vec<Vec<usize>> = serde_wasm_bindgen::from_value(row_clues)?;
+    let col_clues: Vec<Vec<usize>> = serde_wasm_bindgen::from_value(col_clues)?;
+
+    let board_json = BoardJson {
+        rows: row_clues.len(),
+        cols: col_clues.len(),
+        row_clues,
+        col_clues,
+        grid: vec![],
+    };
+    let mut board =
+        Board::try_from(board_json).map_err(|err| JsValue::from_str(&err.to_string()))?;
+
+    let outcome = solver::solve(&mut board, true);
+    let report = SolveReport::new(outcome, board);
+    Ok(serde_wasm_bindgen::to_value(&report)?)
+}
@@ -0,0 +1,110 @@
+/* Exercises the C API, built and run by tests/ffi.rs. The calls are kept out of assert() so
+   they still run with NDEBUG. */
+
+#include <assert.h>
+#include <stdint.h>
+#include <stdio.h>
+
+#include "nonogram_solver.h"
+
+static void test_solve(void) {
+    /* XX
+       X. */
+    size_t row_hints[] = {2, 1};
+    size_t row_hint_counts[] = {1, 1};
+    size_t col_hints[] = {2, 1};
+    size_t col_hint_counts[] = {1, 1};
+
+    NonogramPuzzle *puzzle =
+        nonogram_puzzle_new(2, 2, row_hints, row_hint_counts, col_hints, col_hint_counts);
+    assert(puzzle != NULL);
+    size_t rows = nonogram_puzzle_rows(puzzle);
+    size_t cols = nonogram_puzzle_cols(puzzle);
+    assert(rows == 2 && cols == 2);
+    NonogramStatus status = nonogram_puzzle_status(puzzle);
+    assert(status == NONOGRAM_STATUS_UNSOLVED);
+
+    status = nonogram_puzzle_solve(puzzle, NULL);
+    assert(status == NONOGRAM_STATUS_SOLVED);
+    status = nonogram_puzzle_status(puzzle);
+    assert(status == NONOGRAM_STATUS_SOLVED);
+
+    NonogramCell cell;
+    bool found = nonogram_puzzle_get_cell(puzzle, 0, 1, &cell);
+    assert(found && cell == NONOGRAM_CELL_FILLED);
+    found = nonogram_puzzle_get_cell(puzzle, 1, 1, &cell);
+    assert(found && cell == NONOGRAM_CELL_EMPTY);
+    found = nonogram_puzzle_get_cell(puzzle, 2, 0, &cell);
+    assert(!found);
+
+    nonogram_puzzle_free(puzzle);
+}
+
+static void test_known_cells_and_guessing(void) {
+    /* two possible diagonals, one known cell picks the solution */
+    size_t hints[] = {1, 1};
+    size_t hint_counts[] = {1, 1};
+    NonogramSolveOptions options = {.clear_board = false, .allow_guessing = false};
+
+    NonogramPuzzle *puzzle = nonogram_puzzle_new(2, 2, hints, hint_counts, hints, hint_counts);
+    assert(puzzle != NULL);
+    NonogramStatus status = nonogram_puzzle_solve(puzzle, &options);
+    assert(status == NONOGRAM_STATUS_UNSOLVED);
+
+    options.allow_guessing = true;
+    status = nonogram_puzzle_solve(puzzle, &options);
+    assert(status == NONOGRAM_STATUS_MULTIPLE_SOLUTIONS);
+
+    bool set = nonogram_puzzle_set_cell(puzzle, 0, 0, NONOGRAM_CELL_FILLED);
+    assert(set);
+    set = nonogram_puzzle_set_cell(puzzle, 0, 5, NONOGRAM_CELL_FILLED);
+    assert(!set);
+    set = nonogram_puzzle_set_cell(puzzle, 0, 1, 7);
+    assert(!set);
+    status = nonogram_puzzle_solve(puzzle, &options);
+    assert(status == NONOGRAM_STATUS_SOLVED);
+
+    NonogramCell cell;
+    bool found = nonogram_puzzle_get_cell(puzzle, 1, 1, &cell);
+    assert(found && cell == NONOGRAM_CELL_FILLED);
+    found = nonogram_puzzle_get_cell(puzzle, 0, 1, &cell);
+    assert(found && cell == NONOGRAM_CELL_EMPTY);
+
+    options.clear_board = true;
+    options.allow_guessing = false;
+    status = nonogram_puzzle_solve(puzzle, &options);
+    assert(status == NONOGRAM_STATUS_UNSOLVED);
+    found = nonogram_puzzle_get_cell(puzzle, 0, 0, &cell);
+    assert(found && cell == NONOGRAM_CELL_UNKNOWN);
+
+    nonogram_puzzle_free(puzzle);
+}
+
+static void test_invalid_arguments(void) {
+    size_t hint_counts[] = {1};
+    size_t huge_counts[] = {SIZE_MAX, 1};
+    size_t hints[] = {1, 1};
+
+    NonogramPuzzle *puzzle = nonogram_puzzle_new(1, 1, NULL, hint_counts, NULL, hint_counts);
+    assert(puzzle == NULL);
+    puzzle = nonogram_puzzle_new(2, 1, hints, huge_counts, hints, hint_counts);
+    assert(puzzle == NULL);
+    puzzle = nonogram_puzzle_new(SIZE_MAX, 2, hints, hint_counts, hints, hint_counts);
+    assert(puzzle == NULL);
+
+    NonogramStatus status = nonogram_puzzle_solve(NULL, NULL);
+    assert(status == NONOGRAM_STATUS_INVALID_ARGUMENT);
+    status = nonogram_puzzle_status(NULL);
+    assert(status == NONOGRAM_STATUS_INVALID_ARGUMENT);
+    size_t rows = nonogram_puzzle_rows(NULL);
+    assert(rows == 0);
+    nonogram_puzzle_free(NULL);
+}
+
+int main(void) {
+    test_solve();
+    test_known_cells_and_guessing();
+    test_invalid_arguments();
+    printf("ok\n");
+    return 0;
+}
@@ -0,0 +1,38 @@
+// Builds tests/c/test_ffi.c against the cdylib and runs it
+#![cfg(feature = "ffi")]
+
+use std::env;
+use std::path::PathBuf;
+use std::process::Command;
+
+#[test]
+fn c_test_program() {
+    let root = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    // the cdylib is built next to this test, in target/<profile>/deps
+    let lib_dir = env::current_exe().unwrap().parent().unwrap().to_owned();
+    let program = lib_dir.join("test_ffi");
+
+    let status = Command::new(env::var("CC").unwrap_or_else(|_| "cc".to_owned()))
+        .arg(root.join("tests/c/test_ffi.c"))
+        .arg("-I")
+        .arg(root.join("include"))
+        .arg("-L")
+        .arg(&lib_dir)
+        .arg("-lnonogram_solver")
+        .arg("-o")
+        .arg(&program)
+        .status()
+        .expect("can't run the C compiler");
+    assert!(status.success());
+
+    let output = Command::new(&program)
+        .env("LD_LIBRARY_PATH", &lib_dir)
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "ok\n");
+}
@@ -0,0 +1,197 @@
+// Property tests: random grids are turned into puzzles, solved and checked against the grid
+// they came from. Single line rules are checked against every placement of the hints.
+
+use proptest::prelude::*;
+
+use nonogram_solver::board::{Board, Cell, Clue};
+use nonogram_solver::solver::{self, SolveStatus};
+
+fn cell(painted: bool) -> Cell {
+    if painted {
+        Cell::On
+    } else {
+        Cell::Off
+    }
+}
+
+fn grid() -> impl Strategy<Value = Vec<Vec<bool>>> {
+    (1..=8_usize, 1..=8_usize).prop_flat_map(|(rows, cols)| {
+        prop::collection::vec(prop::collection::vec(any::<bool>(), cols), rows)
+    })
+}
+
+fn puzzle_of(grid: &[Vec<bool>]) -> Board {
+    let mut board = Board::new(grid.len(), grid[0].len());
+    board.data = grid
+        .iter()
+        .map(|row| row.iter().copied().map(cell).collect())
+        .collect();
+    board.generate_clues_from_board();
+    board
+}
+
+fn hints_of(line: &[bool]) -> Vec<usize> {
+    let puzzle = puzzle_of(&[line.to_vec()]);
+    puzzle.row_clues()[0].hints.clone()
+}
+
+// every way to place the hints in a line of `len` cells
+fn placements(hints: &[usize], len: usize) -> Vec<Vec<Cell>> {
+    match hints.split_first() {
+        None => vec![vec![Cell::Off; len]],
+        Some((&hint, rest)) => {
+            let rest_len = rest.iter().map(|h| h + 1).sum::<usize>();
+            if hint + rest_len > len {
+                return vec![];
+            }
+
+            let mut lines = vec![];
+            for start in 0..=len - hint - rest_len {
+                let mut line = vec![Cell::Off; start];
+                line.extend(vec![Cell::On; hint]);
+                if rest.is_empty() {
+                    line.extend(vec![Cell::Off; len - start - hint]);
+                    lines.push(line);
+                    continue;
+                }
+
+                line.push(Cell::Off);
+                for tail in placements(rest, len - line.len()) {
+                    let mut line = line.clone();
+                    line.extend(tail);
+                    lines.push(line);
+                }
+            }
+            lines
+        }
+    }
+}
+
+// the cells that have the same value in every placement agreeing with the known cells
+fn forced_cells(hints: &[usize], known: &[Cell]) -> Vec<Option<Cell>> {
+    let fits = |line: &Vec<Cell>| {
+        line.iter()
+            .zip(known)
+            .all(|(cell, known)| *known == Cell::None || known == cell)
+    };
+    let lines = placements(hints, known.len())
+        .into_iter()
+        .filter(fits)
+        .collect::<Vec<_>>();
+
+    (0..known.len())
+        .map(|i| {
+            let first = lines[0][i];
+            lines.iter().all(|line| line[i] == first).then_some(first)
+        })
+        .collect()
+}
+
+// a line, its hints, and the line with some of its cells forgotten
+fn line() -> impl Strategy<Value = (Vec<usize>, Vec<Cell>, Vec<Cell>)> {
+    (1..=12_usize)
+        .prop_flat_map(|len| {
+            (
+                prop::collection::vec(any::<bool>(), len),
+                prop::collection::vec(prop::bool::weighted(0.3), len),
+            )
+        })
+        .prop_map(|(line, known)| {
+            let solution = line.iter().copied().map(cell).collect::<Vec<_>>();
+            let partial = solution
+                .iter()
+                .zip(known)
+                .map(|(&cell, known)| if known { cell } else { Cell::None })
+                .collect();
+            (hints_of(&line), solution, partial)
+        })
+}
+
+fn check_line_rule(
+    hints: &[usize], partial: &[Cell], rule: impl Fn(&[usize], &mut [Cell]),
+) -> Result<(), TestCaseError> {
+    let forced = forced_cells(hints, partial);
+    let mut line = partial.to_vec();
+    rule(hints, &mut line);
+
+    for (i, (cell, forced)) in line.iter().zip(forced).enumerate() {
+        if *cell != Cell::None {
+            prop_assert_eq!(Some(*cell), forced, "cell {} of {:?}", i, line);
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn placements_match_brute_force() {
+    for len in 1..=8 {
+        for bits in 0..1_u32 << len {
+            let line = (0..len).map(|i| bits & 1 << i != 0).collect::<Vec<_>>();
+            let hints = hints_of(&line);
+            let cells = line.iter().copied().map(cell).collect::<Vec<_>>();
+            assert!(placements(&hints, len).contains(&cells));
+        }
+    }
+    assert_eq!(placements(&[2, 1], 5).len(), 3);
+    assert_eq!(placements(&[3], 2).len(), 0);
+}
+
+proptest! {
+    #[test]
+    fn solving_agrees_with_the_grid(grid in grid()) {
+        let puzzle = puzzle_of(&grid);
+        let mut board = puzzle.clone();
+        let outcome = solver::solve(&mut board, true);
+
+        prop_assert_ne!(outcome.status, SolveStatus::InvalidClues);
+        for (n_row, row) in board.data.iter().enumerate() {
+            for (n_col, &cell) in row.iter().enumerate() {
+                if cell != Cell::None {
+                    prop_assert_eq!(cell, puzzle.data[n_row][n_col], "cell {} {}", n_row, n_col);
+                }
+            }
+        }
+
+        if outcome.is_solved() {
+            let mut regenerated = board.clone();
+            regenerated.generate_clues_from_board();
+            prop_assert_eq!(regenerated.row_clues(), puzzle.row_clues());
+            prop_assert_eq!(regenerated.col_clues(), puzzle.col_clues());
+        }
+    }
+
+    #[test]
+    fn simple_boxes_only_fixes_forced_cells((hints, _solution, partial) in line()) {
+        check_line_rule(&hints, &partial, solver::simple_boxes)?;
+    }
+
+    #[test]
+    fn complete_line_only_fixes_forced_cells((hints, _solution, partial) in line()) {
+        check_line_rule(&hints, &partial, |hints, line| {
+            solver::mark_complete_row(&Clue::new(hints.to_vec()), line)
+        })?;
+    }
+
+    #[test]
+    fn first_and_last_block_only_fixes_forced_cells((hints, _solution, partial) in line()) {
+        check_line_rule(&hints, &partial, |hints, line| {
+            solver::fill_first_and_last_block_if_possible(hints, line)
+        })?;
+    }
+
+    #[test]
+    fn impossible_region_only_fixes_forced_cells((hints, _solution, partial) in line()) {
+        check_line_rule(&hints, &partial, |hints, line| {
+            solver::cross_impossible_region(hints, line)
+        })?;
+    }
+
+    #[test]
+    fn forced_cells_match_the_solution((hints, solution, partial) in line()) {
+        for (forced, cell) in forced_cells(&hints, &partial).into_iter().zip(solution) {
+            if let Some(forced) = forced {
+                prop_assert_eq!(forced, cell);
+            }
+        }
+    }
+}
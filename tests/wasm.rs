@@ -0,0 +1,47 @@
+// Runs under node: cargo test --target wasm32-unknown-unknown --no-default-features
+// (needs wasm-bindgen-cli for the test runner configured in .cargo/config.toml)
+#![cfg(target_arch = "wasm32")]
+
+use serde::Deserialize;
+use wasm_bindgen::JsValue;
+use wasm_bindgen_test::wasm_bindgen_test;
+
+use nonogram_solver::wasm;
+
+#[derive(Deserialize)]
+struct Report {
+    status: String,
+    board: ReportBoard,
+}
+
+#[derive(Deserialize)]
+struct ReportBoard {
+    grid: Vec<String>,
+}
+
+fn clues(clues: &[&[usize]]) -> JsValue {
+    serde_wasm_bindgen::to_value(clues).unwrap()
+}
+
+#[wasm_bindgen_test]
+fn solves_clue_arrays() {
+    let result = wasm::solve(clues(&[&[2], &[1]]), clues(&[&[2], &[1]])).unwrap();
+    let report: Report = serde_wasm_bindgen::from_value(result).unwrap();
+
+    assert_eq!(report.status, "solved");
+    assert_eq!(report.board.grid, vec!["XX", "X."]);
+}
+
+#[wasm_bindgen_test]
+fn reports_unsolved_boards() {
+    let result = wasm::solve(clues(&[&[1], &[1]]), clues(&[&[1], &[1]])).unwrap();
+    let report: Report = serde_wasm_bindgen::from_value(result).unwrap();
+
+    assert_eq!(report.status, "unsolved");
+    assert_eq!(report.board.grid, vec!["  ", "  "]);
+}
+
+#[wasm_bindgen_test]
+fn rejects_malformed_clues() {
+    assert!(wasm::solve(JsValue::from_str("nope"), clues(&[])).is_err());
+}